@@ -0,0 +1,22 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+// Generates the C header for the `ffi` module into `OUT_DIR`; the `ffi` test
+// checks it against the published `include/tmsel.h`
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failed to generate the C header")
+        .write_to_file(format!("{}/tmsel.h", out_dir));
+}
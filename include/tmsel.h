@@ -0,0 +1,72 @@
+#ifndef TMSEL_H
+#define TMSEL_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A parsed scope selector, owned by the caller until passed to `tmsel_free`.
+ */
+typedef struct TmselSelector TmselSelector;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses a scope selector.
+ *
+ * Returns null when the selector is invalid, see `tmsel_last_error`.
+ *
+ * # Safety
+ *
+ * `selector` must be null or point to a NUL-terminated string.
+ */
+struct TmselSelector *tmsel_parse(const char *selector);
+
+/**
+ * Checks a space-separated scope stack against a selector.
+ *
+ * # Safety
+ *
+ * `selector` must come from `tmsel_parse` and `scopes` must be null or
+ * point to a NUL-terminated string.
+ */
+bool tmsel_matches(const struct TmselSelector *selector, const char *scopes);
+
+/**
+ * Returns the `L`, `R` or `B` prefix of the matching selector, or `'\0'`.
+ *
+ * # Safety
+ *
+ * `selector` must come from `tmsel_parse` and `scopes` must be null or
+ * point to a NUL-terminated string.
+ */
+char tmsel_get_prefix(const struct TmselSelector *selector, const char *scopes);
+
+/**
+ * Frees a selector returned by `tmsel_parse`.
+ *
+ * # Safety
+ *
+ * `selector` must be null or come from `tmsel_parse`, and is invalid afterwards.
+ */
+void tmsel_free(struct TmselSelector *selector);
+
+/**
+ * Returns the message of the last error on this thread, or null.
+ *
+ * The string stays valid until the next call into this library on the same thread.
+ */
+const char *tmsel_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TMSEL_H */
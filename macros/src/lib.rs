@@ -0,0 +1,109 @@
+//! Compile-time parsing of Textmate scope selectors.
+//!
+//! `selector!` runs the grammar of `textmate-scope-selector-peg` while the
+//! calling crate is compiled and expands to the matcher tree, so nothing is
+//! parsed at runtime:
+//!
+//! ```
+//! use textmate_scope_selector_peg_macros::selector;
+//!
+//! let selector = selector!("comment, string - string.interpolated");
+//! assert!(selector.matches("source.js string.quoted"));
+//! assert!(!selector.matches("source.js string.interpolated"));
+//! ```
+//!
+//! Invalid selectors are reported as compile errors on the literal:
+//!
+//! ```compile_fail
+//! use textmate_scope_selector_peg_macros::selector;
+//!
+//! let selector = selector!("comment, (string");
+//! ```
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+use textmate_scope_selector_peg::matchers::{Matcher, MatcherNode};
+
+#[proc_macro]
+pub fn selector(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+
+    let expanded = match textmate_scope_selector_peg::parse(&literal.value()) {
+        Ok(matcher) => expand(matcher.as_ref()),
+        Err(err) => {
+            let message = format!("invalid scope selector: {}", err);
+            return syn::Error::new(literal.span(), message).to_compile_error().into();
+        }
+    };
+
+    expanded.into()
+}
+
+// Expands a parsed matcher into the expression constructing it
+fn expand(matcher: &dyn Matcher) -> TokenStream {
+    let matchers = quote!(::textmate_scope_selector_peg::matchers);
+    let private = quote!(::textmate_scope_selector_peg::__private);
+
+    let constructed = match matcher.node() {
+        MatcherNode::Segment(segment) => quote! {
+            #matchers::SegmentMatcher::new(#segment)
+        },
+        MatcherNode::Wildcard => quote! {
+            #matchers::TrueMatcher
+        },
+        MatcherNode::Scope(segments) => {
+            let segments = segments.iter().map(|segment| expand(segment.as_ref()));
+            quote! {
+                #matchers::ScopeMatcher::new(#private::vec![#(#segments),*])
+            }
+        }
+        MatcherNode::Path(prefix, scopes) => {
+            let prefix = expand_prefix(prefix);
+            let scopes = scopes.iter().map(|scope| expand(scope.as_ref()));
+            quote! {
+                #matchers::PathMatcher::new(#prefix, #private::vec![#(#scopes),*])
+            }
+        }
+        MatcherNode::Group(prefix, selector) => {
+            let prefix = expand_prefix(prefix);
+            let selector = expand(selector);
+            quote! {
+                #matchers::GroupMatcher::new(#prefix, #selector)
+            }
+        }
+        MatcherNode::Or(left, right) => {
+            let (left, right) = (expand(left), expand(right));
+            quote! {
+                #matchers::OrMatcher::new(#left, #right)
+            }
+        }
+        MatcherNode::And(left, right) => {
+            let (left, right) = (expand(left), expand(right));
+            quote! {
+                #matchers::AndMatcher::new(#left, #right)
+            }
+        }
+        MatcherNode::Negate(matcher) => {
+            let matcher = expand(matcher);
+            quote! {
+                #matchers::NegateMatcher::new(#matcher)
+            }
+        }
+        _ => unreachable!("the default grammar only produces standard matchers"),
+    };
+
+    quote! {
+        (#private::Box::new(#constructed) as #private::Box<dyn #matchers::Matcher>)
+    }
+}
+
+fn expand_prefix(prefix: Option<char>) -> TokenStream {
+    match prefix {
+        Some(prefix) => {
+            let prefix = prefix.to_string();
+            quote!(::core::option::Option::Some(#prefix))
+        }
+        None => quote!(::core::option::Option::None),
+    }
+}
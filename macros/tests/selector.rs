@@ -0,0 +1,42 @@
+use textmate_scope_selector_peg::matchers::Matcher;
+use textmate_scope_selector_peg_macros::selector;
+
+#[derive(Clone, Debug)]
+struct MacroTestCase {
+    selector: String,
+    input: String,
+}
+
+#[test]
+fn test_selector_macro_matches_runtime_parse() {
+    let compiled: Vec<(&str, Box<dyn Matcher>)> = vec![
+        ("comment, string - string.interpolated", selector!("comment, string - string.interpolated")),
+        ("a.*.c", selector!("a.*.c")),
+        ("a b e", selector!("a b e")),
+        ("a & -b", selector!("a & -b")),
+        ("(a,b) | (c, d)", selector!("(a,b) | (c, d)")),
+        ("L:text.html.markdown - (comment, string)", selector!("L:text.html.markdown - (comment, string)")),
+        ("R:(g)", selector!("R:(g)")),
+    ];
+    let inputs = ["a", "a.b.c", "a b c d e", "b", "c", "g", "source.js string.interpolated", "text.html.markdown", "text.html.markdown comment"];
+
+    for (source, matcher) in &compiled {
+        let parsed = textmate_scope_selector_peg::parse(source).unwrap();
+        for input in inputs {
+            let case = MacroTestCase {
+                selector: source.to_string(),
+                input: input.to_string(),
+            };
+            assert_eq!(
+                matcher.matches(&case.input), parsed.matches(&case.input),
+                "Test failed for selector: \"{}\", input: \"{}\"",
+                case.selector, case.input
+            );
+            assert_eq!(
+                matcher.get_prefix(&case.input), parsed.get_prefix(&case.input),
+                "Test failed for selector: \"{}\", input: \"{}\"",
+                case.selector, case.input
+            );
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::matchers::{alternatives, Matcher, MatcherNode};
+
+// Compiled scope: one entry per segment, `None` standing for `*`
+type Pattern = Vec<Option<String>>;
+
+// How a rule is settled once one of its compiled paths has been walked
+#[derive(Clone, Copy, PartialEq)]
+enum Check {
+    // The path is the whole alternative, so the rule matches
+    Exact,
+    // The path is only required, the rest is evaluated on the tree
+    Filter,
+}
+
+// Trie node over scope segments, shared by all compiled scopes
+#[derive(Default)]
+struct TrieNode {
+    literals: BTreeMap<String, usize>,
+    wildcard: Option<usize>,
+    terminal: bool,
+}
+
+struct CompiledPath {
+    scopes: Vec<usize>,
+    rules: Vec<(usize, Check)>,
+}
+
+// Lazily built DFA state: the progress of every compiled path
+struct State {
+    progress: Vec<usize>,
+    complete: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Cache {
+    states: Vec<State>,
+    ids: BTreeMap<Vec<usize>, usize>,
+    transitions: BTreeMap<(usize, Vec<usize>), usize>,
+    // Times the cache was flushed, which invalidates earlier state ids
+    flushes: usize,
+}
+
+// States kept by `SelectorAutomaton::new` before the cache is flushed
+pub const DEFAULT_CACHE_LIMIT: usize = 4096;
+
+// SelectorAutomaton. The DFA is built while matching, behind a `RefCell`, so
+// an automaton is neither `Sync` nor `Send`; build one per thread
+pub struct SelectorAutomaton {
+    selectors: Vec<Box<dyn Matcher>>,
+    trie: Vec<TrieNode>,
+    paths: Vec<CompiledPath>,
+    fallback: Vec<usize>,
+    cache: RefCell<Cache>,
+    cache_limit: usize,
+}
+
+impl SelectorAutomaton {
+    pub fn new(selectors: Vec<Box<dyn Matcher>>) -> Self {
+        Self::with_cache_limit(selectors, DEFAULT_CACHE_LIMIT)
+    }
+
+    // Flushes the cached states whenever `limit` of them have been built,
+    // bounding memory in long-running processes
+    pub fn with_cache_limit(selectors: Vec<Box<dyn Matcher>>, limit: usize) -> Self {
+        let mut automaton = Self {
+            selectors: Vec::new(),
+            trie: vec![TrieNode::default()],
+            paths: Vec::new(),
+            fallback: Vec::new(),
+            cache: RefCell::new(Cache::default()),
+            cache_limit: limit.max(1),
+        };
+
+        for (index, selector) in selectors.iter().enumerate() {
+            let compiled: Option<Vec<(Vec<Pattern>, Check)>> = alternatives(selector.as_ref())
+                .into_iter()
+                .map(required_path)
+                .collect();
+
+            match compiled {
+                Some(compiled) => {
+                    for (patterns, check) in compiled {
+                        automaton.add_path(patterns, index, check);
+                    }
+                }
+                None => automaton.fallback.push(index),
+            }
+        }
+
+        automaton.selectors = selectors;
+        automaton
+    }
+
+    pub fn len(&self) -> usize {
+        self.selectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selectors.is_empty()
+    }
+
+    pub fn selector(&self, index: usize) -> Option<&dyn Matcher> {
+        self.selectors.get(index).map(|selector| selector.as_ref())
+    }
+
+    // Number of DFA states currently cached
+    pub fn cached_states(&self) -> usize {
+        self.cache.borrow().states.len()
+    }
+
+    // Indices of every selector matching the scopes, in ascending order
+    pub fn matches(&self, scopes: &str) -> Vec<usize> {
+        let mut cache = self.cache.borrow_mut();
+        let mut state = self.state_id(&mut cache, vec![0; self.paths.len()]);
+
+        for scope in scopes.split(' ') {
+            let matched = self.scope_ids(scope);
+            if !matched.is_empty() {
+                state = self.step(&mut cache, state, matched);
+            }
+        }
+
+        let mut exact = vec![false; self.selectors.len()];
+        let mut candidates = self.fallback.clone();
+        for &path in &cache.states[state].complete {
+            for &(rule, check) in &self.paths[path].rules {
+                match check {
+                    Check::Exact => exact[rule] = true,
+                    Check::Filter => candidates.push(rule),
+                }
+            }
+        }
+
+        for rule in candidates {
+            if !exact[rule] && self.selectors[rule].matches(scopes) {
+                exact[rule] = true;
+            }
+        }
+
+        exact
+            .iter()
+            .enumerate()
+            .filter(|(_, matched)| **matched)
+            .map(|(rule, _)| rule)
+            .collect()
+    }
+
+    fn add_path(&mut self, patterns: Vec<Pattern>, rule: usize, check: Check) {
+        let scopes: Vec<usize> = patterns.into_iter().map(|pattern| self.add_scope(pattern)).collect();
+        match self.paths.iter_mut().find(|path| path.scopes == scopes) {
+            Some(path) => path.rules.push((rule, check)),
+            None => self.paths.push(CompiledPath {
+                scopes,
+                rules: vec![(rule, check)],
+            }),
+        }
+    }
+
+    fn add_scope(&mut self, pattern: Pattern) -> usize {
+        let mut node = 0;
+        for segment in pattern {
+            let next = match &segment {
+                Some(literal) => self.trie[node].literals.get(literal).copied(),
+                None => self.trie[node].wildcard,
+            };
+            node = match next {
+                Some(next) => next,
+                None => {
+                    self.trie.push(TrieNode::default());
+                    let next = self.trie.len() - 1;
+                    match segment {
+                        Some(literal) => {
+                            self.trie[node].literals.insert(literal, next);
+                        }
+                        None => self.trie[node].wildcard = Some(next),
+                    }
+                    next
+                }
+            };
+        }
+
+        // Scopes are identified by their terminal trie node
+        self.trie[node].terminal = true;
+        node
+    }
+
+    // Compiled scopes matching a single element of the stack
+    fn scope_ids(&self, scope: &str) -> Vec<usize> {
+        let mut matched = Vec::new();
+        if self.trie[0].terminal {
+            matched.push(0);
+        }
+
+        let mut active = vec![0];
+
+        for segment in scope.split('.') {
+            let mut next = Vec::new();
+            for node in active {
+                let node = &self.trie[node];
+                next.extend(node.literals.get(segment).copied());
+                next.extend(node.wildcard);
+            }
+            matched.extend(next.iter().filter(|&&node| self.trie[node].terminal));
+            if next.is_empty() {
+                break;
+            }
+            active = next;
+        }
+
+        matched.sort_unstable();
+        matched.dedup();
+        matched
+    }
+
+    fn step(&self, cache: &mut Cache, state: usize, matched: Vec<usize>) -> usize {
+        let key = (state, matched);
+        if let Some(&next) = cache.transitions.get(&key) {
+            return next;
+        }
+
+        let progress = cache.states[state]
+            .progress
+            .iter()
+            .zip(self.paths.iter())
+            .map(|(&index, path)| match path.scopes.get(index) {
+                Some(scope) if key.1.binary_search(scope).is_ok() => index + 1,
+                _ => index,
+            })
+            .collect();
+
+        let flushes = cache.flushes;
+        let next = self.state_id(cache, progress);
+        if cache.flushes == flushes {
+            cache.transitions.insert(key, next);
+        }
+        next
+    }
+
+    fn state_id(&self, cache: &mut Cache, progress: Vec<usize>) -> usize {
+        if let Some(&id) = cache.ids.get(&progress) {
+            return id;
+        }
+
+        if cache.states.len() >= self.cache_limit {
+            cache.states.clear();
+            cache.ids.clear();
+            cache.transitions.clear();
+            cache.flushes += 1;
+        }
+
+        let complete = progress
+            .iter()
+            .zip(self.paths.iter())
+            .enumerate()
+            .filter(|(_, (&index, path))| index >= path.scopes.len())
+            .map(|(path, _)| path)
+            .collect();
+
+        let id = cache.states.len();
+        cache.ids.insert(progress.clone(), id);
+        cache.states.push(State { progress, complete });
+        id
+    }
+}
+
+impl FromIterator<Box<dyn Matcher>> for SelectorAutomaton {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Matcher>>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+// Path that must match for the alternative to match, if one can be compiled
+fn required_path(matcher: &dyn Matcher) -> Option<(Vec<Pattern>, Check)> {
+    match matcher.node() {
+        MatcherNode::Path(_, scopes) if !scopes.is_empty() => {
+            let patterns = scopes
+                .iter()
+                .map(|scope| compile_scope(scope.as_ref()))
+                .collect::<Option<Vec<_>>>()?;
+            Some((patterns, Check::Exact))
+        }
+        MatcherNode::Group(_, selector) => required_path(selector),
+        MatcherNode::And(left, right) => required_path(left)
+            .or_else(|| required_path(right))
+            .map(|(patterns, _)| (patterns, Check::Filter)),
+        _ => None,
+    }
+}
+
+fn compile_scope(matcher: &dyn Matcher) -> Option<Pattern> {
+    match matcher.node() {
+        MatcherNode::Scope(segments) => segments
+            .iter()
+            .map(|segment| match segment.node() {
+                MatcherNode::Segment(literal) => Some(Some(literal.to_string())),
+                MatcherNode::Wildcard => Some(None),
+                _ => None,
+            })
+            .collect(),
+        MatcherNode::Segment(literal) => Some(vec![Some(literal.to_string())]),
+        MatcherNode::Wildcard => Some(vec![None]),
+        _ => None,
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+use textmate_scope_selector_peg::json;
+use textmate_scope_selector_peg::matchers::Matcher;
+use textmate_scope_selector_peg::{LineCol, ParseError};
+
+const USAGE: &str = "\
+Usage: tmsel [options] <selector> [<scopes>...]
+
+Evaluates a Textmate scope selector against scope stacks, given as
+arguments or read line by line from stdin, and prints one JSON object
+per stack:
+
+  {\"scopes\":\"source.js string.quoted\",\"matches\":true,\"prefix\":null}
+
+Options:
+  -h, --help     Print this help
+  -V, --version  Print the version";
+
+fn main() -> ExitCode {
+    let mut positional = Vec::new();
+    let mut options_done = false;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--" if !options_done => options_done = true,
+            "-h" | "--help" if !options_done => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            "-V" | "--version" if !options_done => {
+                println!("tmsel {}", env!("CARGO_PKG_VERSION"));
+                return ExitCode::SUCCESS;
+            }
+            option if !options_done && option.starts_with("--") => {
+                eprintln!("error: unknown option {}\n\n{}", option, USAGE);
+                return ExitCode::from(2);
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    if positional.is_empty() {
+        eprintln!("error: missing selector\n\n{}", USAGE);
+        return ExitCode::from(2);
+    }
+    let source = positional.remove(0);
+
+    let selector = match textmate_scope_selector_peg::parse(&source) {
+        Ok(selector) => selector,
+        Err(err) => {
+            eprintln!("{}", render_error(&source, &err));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let result = if positional.is_empty() {
+        io::stdin()
+            .lock()
+            .lines()
+            .try_for_each(|line| evaluate(&mut out, selector.as_ref(), &line?))
+    } else {
+        positional
+            .iter()
+            .try_for_each(|scopes| evaluate(&mut out, selector.as_ref(), scopes))
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn evaluate(out: &mut impl Write, selector: &dyn Matcher, scopes: &str) -> io::Result<()> {
+    let prefix = match selector.get_prefix(scopes) {
+        Some(prefix) => json::string(&prefix.to_string()),
+        None => "null".to_string(),
+    };
+
+    writeln!(
+        out,
+        "{{\"scopes\":{},\"matches\":{},\"prefix\":{}}}",
+        json::string(scopes),
+        selector.matches(scopes),
+        prefix
+    )
+}
+
+// Error message followed by the offending line and a caret under the error
+fn render_error(source: &str, err: &ParseError<LineCol>) -> String {
+    let source = source.replace("\r\n", "\n");
+    let line = source.split(['\n', '\r']).nth(err.location.line - 1).unwrap_or("");
+    let padding: String = line
+        .chars()
+        .take(err.location.column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    format!(
+        "error: expected {} at {}\n  {}\n  {}^",
+        err.expected, err.location, line, padding
+    )
+}
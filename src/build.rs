@@ -0,0 +1,104 @@
+use alloc::boxed::Box;
+#[cfg(feature = "regex")]
+use alloc::string::ToString;
+
+use crate::matchers::{self, Matcher};
+use crate::syntax::{Node, Prefix, Scope, Segment};
+use crate::{line_col, LineCol, ParseOptions, SelectorError};
+
+// Builds the matchers for a syntax tree from the grammar. Errors are located
+// in `selector`, where the parsed input starts at byte `base`
+pub(crate) struct Builder<'s> {
+    pub options: &'s ParseOptions,
+    pub selector: &'s str,
+    pub base: usize,
+}
+
+impl Builder<'_> {
+    pub fn build(&self, node: &Node<'_>) -> Result<Box<dyn Matcher>, SelectorError> {
+        let mut side = [0; 4];
+        Ok(match node {
+            Node::Path { prefix, scopes, .. } => {
+                let scopes = scopes.iter().map(|scope| self.scope(scope)).collect::<Result<_, _>>()?;
+                Box::new(matchers::PathMatcher::new(prefix_str(prefix, &mut side), scopes))
+            }
+            Node::Group { prefix, selector, .. } => {
+                Box::new(matchers::GroupMatcher::new(prefix_str(prefix, &mut side), self.build(selector)?))
+            }
+            Node::Negate { operand, .. } => Box::new(matchers::NegateMatcher::new(self.build(operand)?)),
+            Node::Binary { left, operator, right, .. } => {
+                let (left, right) = (self.build(left)?, self.build(right)?);
+                match operator.symbol {
+                    ',' => Box::new(matchers::OrMatcher::new(left, right)),
+                    symbol => Box::new(matchers::CompositeMatcher::new(left, symbol, right)),
+                }
+            }
+        })
+    }
+
+    fn scope(&self, scope: &Scope<'_>) -> Result<Box<dyn Matcher>, SelectorError> {
+        let segments = scope.segments.iter().map(|segment| self.segment(segment)).collect::<Result<_, _>>()?;
+        Ok(Box::new(matchers::ScopeMatcher::new(segments)))
+    }
+
+    // The grammar checked the segment, so its kind follows from its first
+    // character and its wildcards
+    fn segment(&self, segment: &Segment<'_>) -> Result<Box<dyn Matcher>, SelectorError> {
+        let text = segment.text;
+        let ignore_case = self.options.case_insensitive;
+        Ok(if text.starts_with(['@', '$']) {
+            match self.options.predicates.build(text) {
+                Some(matcher) => Box::new(matchers::PredicateMatcher::new(text, matcher)),
+                None => {
+                    return Err(SelectorError::UnknownPredicate {
+                        name: text.into(),
+                        location: self.location(segment),
+                    })
+                }
+            }
+        } else if text.starts_with('/') {
+            self.regex(&text[1..text.len() - 1], segment)?
+        } else if text == "*" {
+            Box::new(matchers::TrueMatcher {})
+        } else if text.contains(['*', '?']) && ignore_case {
+            Box::new(matchers::GlobSegmentMatcher::ignoring_case(text))
+        } else if text.contains(['*', '?']) {
+            Box::new(matchers::GlobSegmentMatcher::new(text))
+        } else if ignore_case {
+            Box::new(matchers::SegmentMatcher::ignoring_case(text))
+        } else {
+            Box::new(matchers::SegmentMatcher::new(text))
+        })
+    }
+
+    #[cfg(feature = "regex")]
+    fn regex(&self, pattern: &str, segment: &Segment<'_>) -> Result<Box<dyn Matcher>, SelectorError> {
+        let matcher = if self.options.case_insensitive {
+            matchers::RegexSegmentMatcher::ignoring_case(pattern)
+        } else {
+            matchers::RegexSegmentMatcher::new(pattern)
+        };
+        match matcher {
+            Ok(matcher) => Ok(Box::new(matcher)),
+            // Reported for the pattern as written rather than as anchored
+            Err(err) => Err(SelectorError::InvalidRegex {
+                message: regex::Regex::new(pattern).err().unwrap_or(err).to_string(),
+                location: self.location(segment),
+            }),
+        }
+    }
+
+    // The grammar rejects `/…/` segments without the `regex` feature
+    #[cfg(not(feature = "regex"))]
+    fn regex(&self, _: &str, _: &Segment<'_>) -> Result<Box<dyn Matcher>, SelectorError> {
+        unreachable!("regex segment without the `regex` feature")
+    }
+
+    fn location(&self, segment: &Segment<'_>) -> LineCol {
+        line_col(self.selector, self.base + segment.span.start)
+    }
+}
+
+fn prefix_str<'b>(prefix: &Option<Prefix>, buffer: &'b mut [u8; 4]) -> Option<&'b str> {
+    prefix.as_ref().map(|prefix| &*prefix.side.encode_utf8(buffer))
+}
@@ -0,0 +1,112 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::json;
+use crate::matchers::Matcher;
+use crate::specificity::winner;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleCoverage {
+    pub index: usize,
+    // Selector in canonical form
+    pub selector: String,
+    // Scope stacks the selector matched
+    pub matched: usize,
+    // Scope stacks the rule styled, being the most specific match
+    pub won: usize,
+}
+
+// Coverage of theme rules over a corpus of scope stacks
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coverage {
+    pub stacks: usize,
+    pub rules: Vec<RuleCoverage>,
+}
+
+impl Coverage {
+    // Rules that matched no scope stack
+    pub fn unused(&self) -> impl Iterator<Item = &RuleCoverage> {
+        self.rules.iter().filter(|rule| rule.matched == 0)
+    }
+
+    // Rules that matched, but always lost to a more specific or later rule
+    pub fn overridden(&self) -> impl Iterator<Item = &RuleCoverage> {
+        self.rules.iter().filter(|rule| rule.matched > 0 && rule.won == 0)
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        let _ = write!(json, "{{\"stacks\":{},\"rules\":[", self.stacks);
+        for (position, rule) in self.rules.iter().enumerate() {
+            if position > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{{\"index\":{},\"selector\":{},\"matched\":{},\"won\":{}}}",
+                rule.index,
+                json::string(&rule.selector),
+                rule.matched,
+                rule.won
+            );
+        }
+        json.push_str("],\"unused\":[");
+        for (position, rule) in self.unused().enumerate() {
+            if position > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "{}", rule.index);
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+// Table of the rules, with unused rules flagged
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>5}  {:>7}  {:>7}  selector", "rule", "matched", "won")?;
+        for rule in &self.rules {
+            let note = if rule.matched == 0 {
+                "  (unused)"
+            } else if rule.won == 0 {
+                "  (overridden)"
+            } else {
+                ""
+            };
+            writeln!(f, "{:>5}  {:>7}  {:>7}  {}{}", rule.index, rule.matched, rule.won, rule.selector, note)?;
+        }
+        write!(f, "{} scope stacks, {} unused rules", self.stacks, self.unused().count())
+    }
+}
+
+// Counts how often each selector matched and won over the scope stacks
+pub fn coverage<'a>(selectors: &[Box<dyn Matcher>], corpus: impl IntoIterator<Item = &'a str>) -> Coverage {
+    let mut rules: Vec<RuleCoverage> = selectors
+        .iter()
+        .enumerate()
+        .map(|(index, selector)| RuleCoverage {
+            index,
+            selector: selector.to_string(),
+            matched: 0,
+            won: 0,
+        })
+        .collect();
+
+    let mut stacks = 0;
+    for scopes in corpus {
+        stacks += 1;
+        for (rule, selector) in rules.iter_mut().zip(selectors) {
+            if selector.matches(scopes) {
+                rule.matched += 1;
+            }
+        }
+        if let Some(index) = winner(selectors.iter().map(|selector| selector.as_ref()), scopes) {
+            rules[index].won += 1;
+        }
+    }
+
+    Coverage { stacks, rules }
+}
@@ -0,0 +1,109 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::matchers::Matcher;
+use crate::specificity;
+use crate::{LineCol, ParseError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Before,
+    After,
+}
+
+// Error from `diff`, naming the rule set and rule that failed to parse
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffError {
+    pub side: Side,
+    pub index: usize,
+    pub error: ParseError<LineCol>,
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = match self.side {
+            Side::Before => "before",
+            Side::After => "after",
+        };
+        write!(f, "{} rule {}: {}", side, self.index, self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DiffError {}
+
+// Rule that styles a scope stack
+#[derive(Clone, Debug, PartialEq)]
+pub struct Winner<'a, P> {
+    pub index: usize,
+    pub selector: &'a str,
+    pub payload: &'a P,
+}
+
+// Scope stack whose winning rule or payload differs between the rule sets
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change<'a, P> {
+    pub scopes: &'a str,
+    pub before: Option<Winner<'a, P>>,
+    pub after: Option<Winner<'a, P>>,
+}
+
+// Compares two theme rule sets on a corpus of scope stacks. The rule matching
+// deepest in the stack wins, then the most specific, and ties go to the later
+// rule; winners are the same when their selectors print the same and their
+// payloads are equal
+pub fn diff<'a, P: PartialEq>(
+    before: &'a [(&'a str, P)],
+    after: &'a [(&'a str, P)],
+    corpus: &[&'a str],
+) -> Result<Vec<Change<'a, P>>, DiffError> {
+    let before_matchers = parse_rules(before, Side::Before)?;
+    let after_matchers = parse_rules(after, Side::After)?;
+
+    let mut changes = Vec::new();
+    for &scopes in corpus {
+        let old = winner(before, &before_matchers, scopes);
+        let new = winner(after, &after_matchers, scopes);
+
+        let same = match (&old, &new) {
+            (Some(old), Some(new)) => {
+                old.payload == new.payload
+                    && before_matchers[old.index].1 == after_matchers[new.index].1
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if !same {
+            changes.push(Change { scopes, before: old, after: new });
+        }
+    }
+
+    Ok(changes)
+}
+
+// Parsed selector with its canonical form
+type ParsedRule = (Box<dyn Matcher>, String);
+
+fn parse_rules<P>(rules: &[(&str, P)], side: Side) -> Result<Vec<ParsedRule>, DiffError> {
+    rules
+        .iter()
+        .enumerate()
+        .map(|(index, (selector, _))| match crate::parse(selector) {
+            Ok(matcher) => {
+                let canonical = matcher.to_string();
+                Ok((matcher, canonical))
+            }
+            Err(error) => Err(DiffError { side, index, error }),
+        })
+        .collect()
+}
+
+fn winner<'a, P>(rules: &'a [(&'a str, P)], matchers: &[ParsedRule], scopes: &str) -> Option<Winner<'a, P>> {
+    specificity::winner(matchers.iter().map(|(matcher, _)| matcher.as_ref()), scopes).map(|index| Winner {
+        index,
+        selector: rules[index].0,
+        payload: &rules[index].1,
+    })
+}
@@ -0,0 +1,166 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::matchers::{Matcher, MatcherNode};
+
+// Why a selector matched a scope stack or not
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub scopes: Vec<String>,
+    pub trace: Trace,
+}
+
+// Outcome of one matcher of the selector, in canonical form
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub selector: String,
+    pub matched: bool,
+    pub step: Step,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    // Components of a path and the stack index each was found at, in order;
+    // the first component past `found` is the one not found after the last
+    Path { components: Vec<String>, found: Vec<usize> },
+    Group(Box<Trace>),
+    Or(Box<Trace>, Box<Trace>),
+    And(Box<Trace>, Box<Trace>),
+    // Stack index the negated selector matched at, which rejects the stack
+    Negate { inner: Box<Trace>, at: Option<usize> },
+    // A matcher without a structural view, only evaluated
+    Opaque,
+}
+
+impl dyn Matcher + '_ {
+    // Evaluates the selector like `matches`, recording each step
+    pub fn explain(&self, scopes: &str) -> Explanation {
+        let stack: Vec<&str> = scopes.split(' ').collect();
+        Explanation {
+            scopes: stack.iter().map(|scope| scope.to_string()).collect(),
+            trace: trace(self, &stack),
+        }
+    }
+}
+
+fn trace(matcher: &dyn Matcher, stack: &[&str]) -> Trace {
+    let (matched, step) = match matcher.node() {
+        MatcherNode::Path(_, components) => {
+            let mut found = Vec::new();
+            for (index, scope) in stack.iter().enumerate() {
+                match components.get(found.len()) {
+                    Some(component) if component.matches(scope) => found.push(index),
+                    Some(_) => {}
+                    None => break,
+                }
+            }
+            let matched = found.len() == components.len() && !components.is_empty();
+            let components = components.iter().map(|component| component.to_string()).collect();
+            (matched, Step::Path { components, found })
+        }
+        MatcherNode::Group(_, selector) => {
+            let inner = trace(selector, stack);
+            (inner.matched, Step::Group(Box::new(inner)))
+        }
+        MatcherNode::Or(left, right) => {
+            let (left, right) = (trace(left, stack), trace(right, stack));
+            (left.matched || right.matched, Step::Or(Box::new(left), Box::new(right)))
+        }
+        MatcherNode::And(left, right) => {
+            let (left, right) = (trace(left, stack), trace(right, stack));
+            (left.matched && right.matched, Step::And(Box::new(left), Box::new(right)))
+        }
+        MatcherNode::Negate(negated) => {
+            let inner = trace(negated, stack);
+            let at = matched_at(&inner);
+            (!inner.matched, Step::Negate { inner: Box::new(inner), at })
+        }
+        _ => (matcher.matches(&stack.join(" ")), Step::Opaque),
+    };
+
+    Trace {
+        selector: matcher.to_string(),
+        matched,
+        step,
+    }
+}
+
+// Stack index of the last scope a matching trace needed
+fn matched_at(trace: &Trace) -> Option<usize> {
+    if !trace.matched {
+        return None;
+    }
+    match &trace.step {
+        Step::Path { found, .. } => found.last().copied(),
+        Step::Group(inner) => matched_at(inner),
+        Step::Or(left, right) => matched_at(left).or_else(|| matched_at(right)),
+        Step::And(left, right) => matched_at(left).max(matched_at(right)),
+        Step::Negate { .. } | Step::Opaque => None,
+    }
+}
+
+// One line per step, nested steps indented
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_trace(&self.trace, &self.scopes, 0, f)
+    }
+}
+
+fn fmt_trace(trace: &Trace, scopes: &[String], depth: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if depth > 0 {
+        f.write_str("\n")?;
+    }
+    write!(f, "{:indent$}", "", indent = depth * 2)?;
+    let outcome = if trace.matched { "matched" } else { "failed" };
+    write!(f, "{} \"{}\"", outcome, trace.selector)?;
+
+    let at = |index: usize| {
+        let scope = scopes.get(index).map_or("", String::as_str);
+        alloc::format!("{} ({})", index, scope)
+    };
+    match &trace.step {
+        Step::Path { components, found } => match (components.get(found.len()), found.last()) {
+            (Some(missing), Some(&last)) => write!(f, ": \"{}\" not found after {}", missing, at(last)),
+            (Some(missing), None) => write!(f, ": \"{}\" not found in the stack", missing),
+            (None, _) => {
+                f.write_str(": ")?;
+                for (position, (component, &index)) in components.iter().zip(found).enumerate() {
+                    if position > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "\"{}\" at {}", component, at(index))?;
+                }
+                Ok(())
+            }
+        },
+        Step::Group(inner) => fmt_trace(inner, scopes, depth + 1, f),
+        Step::Or(left, right) => {
+            if !trace.matched {
+                f.write_str(": no alternative matched")?;
+            }
+            fmt_trace(left, scopes, depth + 1, f)?;
+            fmt_trace(right, scopes, depth + 1, f)
+        }
+        Step::And(left, right) => {
+            match (left.matched, right.matched) {
+                (false, false) => f.write_str(": both sides failed")?,
+                (false, true) => f.write_str(": left side failed")?,
+                (true, false) => f.write_str(": right side failed")?,
+                (true, true) => {}
+            }
+            fmt_trace(left, scopes, depth + 1, f)?;
+            fmt_trace(right, scopes, depth + 1, f)
+        }
+        Step::Negate { inner, at: index } => {
+            match index {
+                Some(index) => write!(f, ": negated selector matched at {}", at(*index))?,
+                None if !trace.matched => f.write_str(": negated selector matched")?,
+                None => {}
+            }
+            fmt_trace(inner, scopes, depth + 1, f)
+        }
+        Step::Opaque => Ok(()),
+    }
+}
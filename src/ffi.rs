@@ -0,0 +1,116 @@
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::string::ToString;
+use core::cell::RefCell;
+use core::ffi::{c_char, CStr};
+use core::ptr;
+
+use std::thread_local;
+
+use crate::matchers::Matcher;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: Option<&str>) {
+    let message = message.map(|message| CString::new(message.replace('\0', " ")).unwrap());
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+}
+
+// Borrows a NUL-terminated UTF-8 string, recording an error when it is invalid
+unsafe fn borrow_str<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        set_last_error(Some("unexpected null pointer"));
+        return None;
+    }
+
+    match CStr::from_ptr(string).to_str() {
+        Ok(string) => Some(string),
+        Err(_) => {
+            set_last_error(Some("string is not valid UTF-8"));
+            None
+        }
+    }
+}
+
+/// A parsed scope selector, owned by the caller until passed to `tmsel_free`.
+pub struct TmselSelector {
+    matcher: Box<dyn Matcher>,
+}
+
+/// Parses a scope selector.
+///
+/// Returns null when the selector is invalid, see `tmsel_last_error`.
+///
+/// # Safety
+///
+/// `selector` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tmsel_parse(selector: *const c_char) -> *mut TmselSelector {
+    let Some(selector) = borrow_str(selector) else {
+        return ptr::null_mut();
+    };
+
+    match crate::parse(selector) {
+        Ok(matcher) => {
+            set_last_error(None);
+            Box::into_raw(Box::new(TmselSelector { matcher }))
+        }
+        Err(err) => {
+            set_last_error(Some(&err.to_string()));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Checks a space-separated scope stack against a selector.
+///
+/// # Safety
+///
+/// `selector` must come from `tmsel_parse` and `scopes` must be null or
+/// point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tmsel_matches(selector: *const TmselSelector, scopes: *const c_char) -> bool {
+    match (selector.as_ref(), borrow_str(scopes)) {
+        (Some(selector), Some(scopes)) => selector.matcher.matches(scopes),
+        _ => false,
+    }
+}
+
+/// Returns the `L`, `R` or `B` prefix of the matching selector, or `'\0'`.
+///
+/// # Safety
+///
+/// `selector` must come from `tmsel_parse` and `scopes` must be null or
+/// point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn tmsel_get_prefix(selector: *const TmselSelector, scopes: *const c_char) -> c_char {
+    match (selector.as_ref(), borrow_str(scopes)) {
+        (Some(selector), Some(scopes)) => selector.matcher.get_prefix(scopes).map_or(0, |prefix| prefix as c_char),
+        _ => 0,
+    }
+}
+
+/// Frees a selector returned by `tmsel_parse`.
+///
+/// # Safety
+///
+/// `selector` must be null or come from `tmsel_parse`, and is invalid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn tmsel_free(selector: *mut TmselSelector) {
+    if !selector.is_null() {
+        drop(Box::from_raw(selector));
+    }
+}
+
+/// Returns the message of the last error on this thread, or null.
+///
+/// The string stays valid until the next call into this library on the same thread.
+#[no_mangle]
+pub extern "C" fn tmsel_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some(message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
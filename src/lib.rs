@@ -1,79 +1,395 @@
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 extern crate peg;
 
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub use options::{ParseMode, ParseOptions, SelectorError, StrictIssue};
 pub use peg::{error::ParseError, str::LineCol};
+pub use scope::{ScopeLike, ScopeStackLike, Segments};
 
+pub mod automaton;
+mod build;
+pub mod coverage;
+pub mod diff;
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+// Shared with the `tmsel` binary, not part of the public API
+#[doc(hidden)]
+pub mod json;
+pub mod lint;
+pub mod list;
 pub mod matchers;
+pub mod options;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod recover;
+pub mod scope;
+pub mod semantic;
+pub mod specificity;
+pub mod suggest;
+pub mod syntax;
+#[cfg(feature = "syntect")]
+pub mod syntect;
+pub mod tree_sitter;
+pub mod validate;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+
+// Paths used by code generated in `textmate-scope-selector-peg-macros`
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::boxed::Box;
+    pub use alloc::vec;
+}
+
+// Matcher entry points of the grammar, which parses into a `syntax::Node`
+pub mod parser {
+    use alloc::boxed::Box;
+
+    use crate::{matchers, syntax_parser, LineCol, ParseError, ParseOptions, SelectorError};
+
+    // Errors are located as by the generated parser, which only counts `\n`
+    // as a line break
+    pub fn parse(input: &str) -> Result<Box<dyn matchers::Matcher>, ParseError<LineCol>> {
+        let node = syntax_parser::parse(input)?;
+        Ok(crate::build_default(&node, input, 0))
+    }
+
+    pub fn parse_with_options(input: &str, options: &ParseOptions) -> Result<Box<dyn matchers::Matcher>, SelectorError> {
+        crate::parse_with_options(input, options)
+    }
+}
 
 peg::parser! {
-    pub grammar parser() for str {
+    pub(crate) grammar syntax_parser() for str {
         // Skip whitespace
-        rule _() = quiet!{ [' ' | '\t']* }
-        rule ws() = quiet!{ [' ' | '\t']+ }
+        rule _() = quiet!{ [' ' | '\t' | '\n' | '\r']* }
+        rule ws() = quiet!{ [' ' | '\t' | '\n' | '\r']+ }
 
         // Starting rule
-        pub rule parse() -> Box<dyn matchers::Matcher>
-            = _ selector:selector() _ {
+        pub rule parse() -> syntax::Node<'input>
+            = parse_with_options(&ParseOptions::DEFAULT)
+
+        pub rule parse_with_options(options: &ParseOptions) -> syntax::Node<'input>
+            = _ selector:selector(options, true) _ {
                 selector
             }
 
-        // Atom matchers
-        rule atom() -> Box<dyn matchers::Matcher>
-            = segment:$(!['-']['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '+']+) {
-                Box::new(matchers::SegmentMatcher::new(segment))
+        // Single alternative of a selector, for error recovery
+        pub rule alternative(options: &ParseOptions) -> syntax::Node<'input>
+            = _ composite:composite(options, true) _ {
+                composite
+            }
+
+        // Succeeds only in `ParseMode::Strict`
+        rule strict(options: &ParseOptions)
+            = {? if options.mode == ParseMode::Strict { Ok(()) } else { Err("ParseMode::Strict") } }
+
+        // Segment characters, optionally extended to Unicode identifier characters
+        rule segment_char(options: &ParseOptions)
+            = ['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '+']
+            / [c if options.unicode_segments && !c.is_ascii() && unicode_ident::is_xid_continue(c)]
+
+        // Host-defined predicate, as in `@readonly` or `$inInjection`
+        rule predicate() -> &'input str
+            = $(['@' | '$'] ['a'..='z' | 'A'..='Z' | '_'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_']*)
+
+        // Segment containing `*` or `?` wildcards, other than a lone `*`
+        rule glob(options: &ParseOptions) -> &'input str
+            = !("*" !glob_char(options)) glob:$(!['-'] segment_char(options)* ['*' | '?'] glob_char(options)*) {
+                glob
+            }
+
+        rule glob_char(options: &ParseOptions)
+            = segment_char(options) / ['*' | '?']
+
+        // Regular expression between `/` delimiters, where `\/` stands for `/`
+        rule regex() -> &'input str
+            = $("/" quiet!{ ("\\" [^ '\n' | '\r'] / [^ '/' | '\\' | '\n' | '\r'])+ } "/")
+
+        // Atoms, checked against the options; `build::Builder` turns them
+        // into matchers
+        rule atom(options: &ParseOptions) -> &'input str
+            = name:quiet!{ predicate() } {?
+                if options.predicates.contains(name) {
+                    Ok(name)
+                } else {
+                    Err(UNKNOWN_PREDICATE)
+                }
+            }
+            / regex:regex() {?
+                regex_segments(options).map(|_| regex)
             }
-            / "*" {
-                Box::new(matchers::TrueMatcher{})
+            / glob:glob(options) {?
+                if !options.glob_segments {
+                    Err("ParseOptions::glob_segments to be enabled for `*` and `?` within a segment")
+                } else if options.mode == ParseMode::Strict && glob.ends_with('-') {
+                    Err(AMBIGUOUS_HYPHEN)
+                } else {
+                    Ok(glob)
+                }
+            }
+            / segment:$(!['-'] segment_char(options)+) {?
+                if options.mode == ParseMode::Strict && segment.ends_with('-') {
+                    Err(AMBIGUOUS_HYPHEN)
+                } else {
+                    Ok(segment)
+                }
             }
+            / $("*")
 
-        // Scope matcher
-        rule scope() -> Box<dyn matchers::Matcher>
-            = atoms:atom() ++ "." {
-                Box::new(matchers::ScopeMatcher::new(atoms))
+        rule segment(options: &ParseOptions) -> syntax::Segment<'input>
+            = start:position!() text:atom(options) end:position!() {
+                syntax::Segment { text, span: start..end }
             }
 
-        // Path matcher
-        rule path() -> Box<dyn matchers::Matcher>
-            = prefix:$(['L'|'R'|'B'] ":")? scopes:scope() ++ ws() {
-                Box::new(matchers::PathMatcher::new(prefix, scopes))
+        rule scope(options: &ParseOptions) -> syntax::Scope<'input>
+            = start:position!() segments:segment(options) ++ "." end:position!() {
+                syntax::Scope { segments, span: start..end }
             }
 
-        // Group matcher
-        rule group() -> Box<dyn matchers::Matcher>
-            = prefix:$(['L'|'R'|'B'] ":")? "(" _ selector:selector() _ ")" {
-                Box::new(matchers::GroupMatcher::new(prefix, selector))
+        // Injection prefix, which strict mode only accepts leading an alternative
+        rule prefix(options: &ParseOptions, leading: bool) -> syntax::Prefix
+            = start:position!() side:$(['L'|'R'|'B']) ":" {?
+                if leading || options.mode == ParseMode::Lenient {
+                    Ok(syntax::Prefix { side: side.chars().next().unwrap(), span: start..start + 2 })
+                } else {
+                    Err(STRAY_PREFIX)
+                }
             }
 
-        // Expression matcher
-        rule expression() -> Box<dyn matchers::Matcher>
-            = "-" _ group:group() _ {
-                Box::new(matchers::NegateMatcher::new(group))
+        rule path(options: &ParseOptions, leading: bool) -> syntax::Node<'input>
+            = start:position!() prefix:prefix(options, leading)? scopes:scope(options) ++ ws() end:position!() {
+                syntax::Node::Path { prefix, scopes, span: start..end }
             }
-            / "-" _ path:path() _ {
-                Box::new(matchers::NegateMatcher::new(path))
+
+        rule group(options: &ParseOptions, leading: bool) -> syntax::Node<'input>
+            = start:position!() prefix:prefix(options, leading)?
+              open:position!() "(" _ selector:selector(options, false) _ close:position!() ")" end:position!() {
+                syntax::Node::Group {
+                    prefix,
+                    open: open..open + 1,
+                    selector: Box::new(selector),
+                    close: close..close + 1,
+                    span: start..end,
+                }
             }
-            / group()
-            / path()
 
-        // Composite matcher
-        rule composite() -> Box<dyn matchers::Matcher>
-            = left:expression() _ operator:$(['|' | '&' | '-']) _ right:composite() {
-                Box::new(matchers::CompositeMatcher::new(left, operator.chars().next().unwrap(), right))
+        rule expression(options: &ParseOptions, leading: bool) -> syntax::Node<'input>
+            = start:position!() "-" _ operand:(group(options, false) / path(options, false)) {
+                let span = start..operand.span().end;
+                syntax::Node::Negate { operator: start..start + 1, operand: Box::new(operand), span }
             }
-            / expression()
+            / group(options, leading)
+            / path(options, leading)
 
-        // Selector matcher
-        rule selector() -> Box<dyn matchers::Matcher>
-            = left:composite() _ "," _ right:selector()? {
-                if let Some(r) = right {
-                    Box::new(matchers::OrMatcher::new(left, r))
-                } else {
-                    left
+        rule operator() -> syntax::Operator
+            = _ start:position!() operator:$(['|' | '&' | '-']) {
+                syntax::Operator { symbol: operator.chars().next().unwrap(), span: start..start + 1 }
+            }
+
+        rule composite(options: &ParseOptions, leading: bool) -> syntax::Node<'input>
+            = left:expression(options, leading) operator:operator() _ right:composite(options, false) {
+                binary(left, operator, right)
+            }
+            / expression(options, leading)
+
+        // Alternatives at the top level may start with a prefix
+        rule selector(options: &ParseOptions, top: bool) -> syntax::Node<'input>
+            = quiet!{ &strict(options) "," } {?
+                Err(EMPTY_ALTERNATIVE)
+            }
+            / left:composite(options, top) _ comma:position!() "," _ right:selector(options, top)? {?
+                match right {
+                    Some(right) => Ok(binary(left, syntax::Operator { symbol: ',', span: comma..comma + 1 }, right)),
+                    None if options.mode == ParseMode::Strict => Err(TRAILING_COMMA),
+                    None => Ok(left),
                 }
             }
-            / composite()
+            / composite(options, top)
     }
 }
 
 pub fn parse(selector: &str) -> Result<Box<dyn matchers::Matcher>, ParseError<LineCol>> {
-    return parser::parse(selector);
+    let node = syntax_parser::parse(selector).map_err(|err| relocate(selector, err))?;
+    Ok(build_default(&node, selector, 0))
+}
+
+// Parses a selector with grammar and matching extensions enabled
+pub fn parse_with_options(selector: &str, options: &ParseOptions) -> Result<Box<dyn matchers::Matcher>, SelectorError> {
+    let node = syntax_parser::parse_with_options(selector, options)
+        .map_err(|err| selector_error(selector, relocate(selector, err)))?;
+    build::Builder { options, selector, base: 0 }.build(&node)
+}
+
+// Parses a selector into a syntax tree keeping the byte range of every node,
+// for editor tooling
+pub fn parse_spanned(selector: &str) -> Result<syntax::Node<'_>, ParseError<LineCol>> {
+    syntax_parser::parse(selector).map_err(|err| relocate(selector, err))
+}
+
+// Only `parse_with_options` compiles `/…/` segments, and reports invalid ones
+pub fn parse_spanned_with_options<'a>(
+    selector: &'a str,
+    options: &ParseOptions,
+) -> Result<syntax::Node<'a>, SelectorError> {
+    syntax_parser::parse_with_options(selector, options).map_err(|err| selector_error(selector, relocate(selector, err)))
+}
+
+// Matchers for a tree parsed with the default options, which the grammar
+// fully checks
+fn build_default(node: &syntax::Node<'_>, selector: &str, base: usize) -> Box<dyn matchers::Matcher> {
+    let builder = build::Builder {
+        options: &ParseOptions::DEFAULT,
+        selector,
+        base,
+    };
+    match builder.build(node) {
+        Ok(matcher) => matcher,
+        Err(err) => unreachable!("{}", err),
+    }
+}
+
+fn binary<'a>(left: syntax::Node<'a>, operator: syntax::Operator, right: syntax::Node<'a>) -> syntax::Node<'a> {
+    let span = left.span().start..right.span().end;
+    syntax::Node::Binary {
+        left: Box::new(left),
+        operator,
+        right: Box::new(right),
+        span,
+    }
+}
+
+// Structured error for the marker tokens below. Each failure is marked right
+// after the offending input, which is found again from there
+fn selector_error(selector: &str, err: ParseError<LineCol>) -> SelectorError {
+    let end = err.location.offset;
+    let strict = |issue, start| SelectorError::Strict {
+        issue,
+        location: line_col(selector, start),
+    };
+    let marker = [UNKNOWN_PREDICATE, STRAY_PREFIX, AMBIGUOUS_HYPHEN, EMPTY_ALTERNATIVE, TRAILING_COMMA]
+        .into_iter()
+        .find(|marker| err.expected.tokens().any(|token| token == *marker));
+    match marker {
+        Some(UNKNOWN_PREDICATE) => {
+            let start = selector[..end].rfind(['@', '$']).unwrap_or(end);
+            SelectorError::UnknownPredicate {
+                name: selector[start..end].to_string(),
+                location: line_col(selector, start),
+            }
+        }
+        Some(STRAY_PREFIX) => strict(StrictIssue::StrayPrefix, end - 2),
+        Some(AMBIGUOUS_HYPHEN) => strict(StrictIssue::AmbiguousHyphen, end - 1),
+        Some(EMPTY_ALTERNATIVE) => strict(StrictIssue::EmptyAlternative, end - 1),
+        // Only whitespace follows the comma
+        Some(TRAILING_COMMA) => strict(StrictIssue::TrailingComma, selector[..end].rfind(',').unwrap_or(end)),
+        _ => SelectorError::Syntax(err),
+    }
+}
+
+// Parses a list of selectors, such as a JSON array of scopes, into one Or selector
+pub fn parse_many(selectors: &[&str]) -> Result<list::SelectorList, list::ParseManyError> {
+    let mut items = Vec::with_capacity(selectors.len());
+    for (index, selector) in selectors.iter().enumerate() {
+        match parse(selector) {
+            Ok(matcher) => items.push(matcher),
+            Err(error) => return Err(list::ParseManyError { index, error }),
+        }
+    }
+    Ok(list::SelectorList::new(items))
+}
+
+// Parses the alternatives of a selector that parse, collecting errors for the others
+pub fn parse_recovering(selector: &str) -> recover::Recovered {
+    recover::recover(selector, false, |chunk, start| match chunk {
+        // A trailing comma is accepted, as in `parse`
+        recover::Chunk::Trailing => None,
+        recover::Chunk::Alternative(chunk) => Some(match syntax_parser::alternative(chunk, &ParseOptions::DEFAULT) {
+            Ok(node) => Ok(build_default(&node, selector, start)),
+            Err(mut err) => {
+                err.location = line_col(selector, start + err.location.offset);
+                Err(err)
+            }
+        }),
+    })
+}
+
+// Parses the alternatives of a selector that parse with extensions enabled,
+// collecting errors for the others
+pub fn parse_recovering_with_options(selector: &str, options: &ParseOptions) -> recover::Recovered<SelectorError> {
+    let strict = |issue, offset| SelectorError::Strict {
+        issue,
+        location: line_col(selector, offset),
+    };
+    recover::recover(selector, options.regex_segments, |chunk, start| match chunk {
+        // The comma before the empty chunk
+        recover::Chunk::Trailing if options.mode == ParseMode::Strict => {
+            Some(Err(strict(StrictIssue::TrailingComma, start - 1)))
+        }
+        recover::Chunk::Trailing => None,
+        // The comma after the empty chunk
+        recover::Chunk::Alternative(chunk)
+            if options.mode == ParseMode::Strict && chunk.trim_matches(recover::WHITESPACE).is_empty() =>
+        {
+            Some(Err(strict(StrictIssue::EmptyAlternative, start + chunk.len())))
+        }
+        recover::Chunk::Alternative(chunk) => Some(match syntax_parser::alternative(chunk, options) {
+            Ok(node) => build::Builder { options, selector, base: start }.build(&node),
+            Err(mut err) => {
+                err.location = line_col(selector, start + err.location.offset);
+                Err(selector_error(selector, err))
+            }
+        }),
+    })
+}
+
+const UNKNOWN_PREDICATE: &str = "a predicate registered in ParseOptions::predicates";
+const STRAY_PREFIX: &str = "no prefix outside the start of an alternative in ParseMode::Strict";
+const AMBIGUOUS_HYPHEN: &str = "no ambiguous `-` in ParseMode::Strict";
+const EMPTY_ALTERNATIVE: &str = "no empty alternative in ParseMode::Strict";
+const TRAILING_COMMA: &str = "no trailing `,` in ParseMode::Strict";
+
+// Whether the options accept `/…/` segments
+fn regex_segments(options: &ParseOptions) -> Result<(), &'static str> {
+    if !cfg!(feature = "regex") {
+        Err("the `regex` feature to be enabled for `/…/` segments")
+    } else if !options.regex_segments {
+        Err("ParseOptions::regex_segments to be enabled for `/…/` segments")
+    } else {
+        Ok(())
+    }
+}
+
+// Line and column of a byte offset, counting `\r\n`, `\n` and `\r` as line breaks
+pub fn line_col(source: &str, offset: usize) -> LineCol {
+    let before = &source[..offset];
+    let mut line = 1;
+    let mut line_start = 0;
+    for (index, c) in before.char_indices() {
+        let crlf = c == '\r' && source[index + 1..].starts_with('\n');
+        if (c == '\n' || c == '\r') && !crlf {
+            line += 1;
+            line_start = index + 1;
+        }
+    }
+
+    LineCol {
+        line,
+        column: before[line_start..].chars().count() + 1,
+        offset,
+    }
+}
+
+fn relocate(source: &str, mut err: ParseError<LineCol>) -> ParseError<LineCol> {
+    err.location = line_col(source, err.location.offset);
+    err
 }
\ No newline at end of file
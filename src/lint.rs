@@ -0,0 +1,252 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::matchers::{alternatives, conjuncts, Matcher, MatcherNode};
+use crate::specificity::specificity;
+use crate::{LineCol, ParseError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintKind {
+    // The selector does not parse
+    Syntax(ParseError<LineCol>),
+    // Same selector as an earlier rule, once printed in canonical form
+    Duplicate { first: usize },
+    // An earlier rule matches every scope stack this one does, and is more
+    // specific, so it always wins over this later rule
+    Shadowed { by: usize },
+    // Every alternative negates something its positive part implies
+    NeverMatches,
+    // `L:`, `R:` and `B:` only mean something in injection selectors
+    Prefix,
+    // A `,` with nothing after it, which the grammar silently drops
+    TrailingComma { offset: usize },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub index: usize,
+    pub kind: LintKind,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        match self.kind {
+            LintKind::Syntax(_) | LintKind::NeverMatches => Severity::Error,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rule {}: ", self.index)?;
+        match &self.kind {
+            LintKind::Syntax(err) => write!(f, "invalid selector: {}", err),
+            LintKind::Duplicate { first } => write!(f, "duplicate of rule {}", first),
+            LintKind::Shadowed { by } => write!(f, "shadowed by rule {}, which is more specific everywhere this rule matches", by),
+            LintKind::NeverMatches => f.write_str("selector can never match"),
+            LintKind::Prefix => f.write_str("prefixes only apply to injection selectors"),
+            LintKind::TrailingComma { offset } => write!(f, "trailing comma at offset {}", offset),
+        }
+    }
+}
+
+// Checks the selectors of a theme's rules, in order
+pub fn lint(selectors: &[&str]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut parsed: Vec<(usize, Box<dyn Matcher>, String)> = Vec::new();
+
+    for (index, source) in selectors.iter().enumerate() {
+        let matcher = match crate::parse(source) {
+            Ok(matcher) => matcher,
+            Err(err) => {
+                diagnostics.push(Diagnostic { index, kind: LintKind::Syntax(err) });
+                continue;
+            }
+        };
+
+        if let Some(offset) = trailing_comma(source) {
+            diagnostics.push(Diagnostic { index, kind: LintKind::TrailingComma { offset } });
+        }
+        if has_prefix(matcher.as_ref()) {
+            diagnostics.push(Diagnostic { index, kind: LintKind::Prefix });
+        }
+
+        let canonical = matcher.to_string();
+        if never_matches(matcher.as_ref()) {
+            diagnostics.push(Diagnostic { index, kind: LintKind::NeverMatches });
+        } else if let Some((first, _, _)) = parsed.iter().find(|(_, _, earlier)| *earlier == canonical) {
+            diagnostics.push(Diagnostic { index, kind: LintKind::Duplicate { first: *first } });
+        } else if let Some((by, _, _)) = parsed
+            .iter()
+            .find(|(_, earlier, _)| shadows(earlier.as_ref(), matcher.as_ref()))
+        {
+            diagnostics.push(Diagnostic { index, kind: LintKind::Shadowed { by: *by } });
+        }
+
+        parsed.push((index, matcher, canonical));
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.index);
+    diagnostics
+}
+
+// Byte offset of a comma followed only by whitespace up to the end or a `)`
+fn trailing_comma(source: &str) -> Option<usize> {
+    source.char_indices().find_map(|(offset, c)| {
+        let rest = source[offset + c.len_utf8()..].trim_start();
+        (c == ',' && (rest.is_empty() || rest.starts_with(')'))).then_some(offset)
+    })
+}
+
+fn has_prefix(matcher: &dyn Matcher) -> bool {
+    match matcher.node() {
+        MatcherNode::Path(prefix, _) => prefix.is_some(),
+        MatcherNode::Group(prefix, selector) => prefix.is_some() || has_prefix(selector),
+        MatcherNode::Or(left, right) | MatcherNode::And(left, right) => has_prefix(left) || has_prefix(right),
+        MatcherNode::Negate(negated) => has_prefix(negated),
+        _ => false,
+    }
+}
+
+fn never_matches(matcher: &dyn Matcher) -> bool {
+    alternatives(matcher).into_iter().all(|alternative| {
+        let conjuncts = conjuncts(alternative);
+        conjuncts.iter().any(|&(positive, negated)| {
+            !positive
+                && (is_universal(negated)
+                    || conjuncts
+                        .iter()
+                        .any(|&(positive, conjunct)| positive && implies(conjunct, negated)))
+        })
+    })
+}
+
+// Every alternative of `later` is covered by an alternative of `earlier` that
+// matches as deep into every stack and is strictly more specific, so `earlier`
+// wins wherever `later` matches; on a tie the later rule wins
+fn shadows(earlier: &dyn Matcher, later: &dyn Matcher) -> bool {
+    let earlier = alternatives(earlier);
+    alternatives(later).into_iter().all(|later| {
+        earlier.iter().any(|&earlier| {
+            implies(later, earlier) && reaches(later, earlier) && specificity(earlier) > specificity(later)
+        })
+    })
+}
+
+// Whether `right` matches a scope at least as deep in the stack as `left`
+// does: the last scope of each positive path of `left` is generalized by the
+// last scope of a positive path of `right`, whose other scopes generalize the
+// ones before it in order
+fn reaches(left: &dyn Matcher, right: &dyn Matcher) -> bool {
+    match (paths(left), paths(right)) {
+        (Some(left), Some(right)) => left.iter().all(|left| {
+            right.iter().any(|right| match (left.split_last(), right.split_last()) {
+                (Some((left_last, left)), Some((right_last, right))) => {
+                    scope_implies(left_last.as_ref(), right_last.as_ref()) && (right.is_empty() || path_implies(left, right))
+                }
+                _ => false,
+            })
+        }),
+        _ => false,
+    }
+}
+
+// Scopes of the positive operands of an alternative, if they are all paths
+fn paths(alternative: &dyn Matcher) -> Option<Vec<&[Box<dyn Matcher>]>> {
+    conjuncts(alternative)
+        .into_iter()
+        .filter(|(positive, _)| *positive)
+        .map(|(_, conjunct)| match conjunct.node() {
+            MatcherNode::Path(_, scopes) => Some(scopes),
+            _ => None,
+        })
+        .collect()
+}
+
+// Whether `right` matches every scope stack `left` matches, as far as can be
+// shown from the structure of both selectors
+fn implies(left: &dyn Matcher, right: &dyn Matcher) -> bool {
+    let right_alternatives = alternatives(right);
+    alternatives(left).into_iter().all(|left| {
+        let left = conjuncts(left);
+        right_alternatives.iter().any(|&right| {
+            conjuncts(right).into_iter().all(|(positive, right)| {
+                left.iter().any(|&(left_positive, left)| match (positive, left_positive) {
+                    (true, true) => operand_implies(left, right),
+                    (false, false) => operand_implies(right, left),
+                    _ => false,
+                })
+            })
+        })
+    })
+}
+
+fn operand_implies(left: &dyn Matcher, right: &dyn Matcher) -> bool {
+    match (left.node(), right.node()) {
+        (MatcherNode::Path(_, left), MatcherNode::Path(_, right)) => path_implies(left, right),
+        (MatcherNode::Group(..) | MatcherNode::Or(..), _) | (_, MatcherNode::Group(..) | MatcherNode::Or(..)) => {
+            implies(left, right)
+        }
+        _ => false,
+    }
+}
+
+// Each scope of `right` generalizes a scope of `left`, in the same order
+fn path_implies(left: &[Box<dyn Matcher>], right: &[Box<dyn Matcher>]) -> bool {
+    let mut remaining = right.iter().peekable();
+    for scope in left {
+        if let Some(next) = remaining.peek() {
+            if scope_implies(scope.as_ref(), next.as_ref()) {
+                remaining.next();
+            }
+        }
+    }
+    !right.is_empty() && remaining.peek().is_none()
+}
+
+fn scope_implies(left: &dyn Matcher, right: &dyn Matcher) -> bool {
+    match (segments(left), segments(right)) {
+        (Some(left), Some(right)) => {
+            right.len() <= left.len()
+                && right
+                    .iter()
+                    .zip(left.iter())
+                    .all(|(right, left)| right.is_none() || right == left)
+        }
+        _ => false,
+    }
+}
+
+// Literal segments of a scope, with `None` standing for `*`
+fn segments(scope: &dyn Matcher) -> Option<Vec<Option<String>>> {
+    let segment = |matcher: &dyn Matcher| match matcher.node() {
+        MatcherNode::Segment(segment) => Some(Some(segment.to_string())),
+        MatcherNode::Wildcard => Some(None),
+        _ => None,
+    };
+
+    match scope.node() {
+        MatcherNode::Scope(segments) => segments.iter().map(|matcher| segment(matcher.as_ref())).collect(),
+        _ => segment(scope).map(|segment| alloc::vec![segment]),
+    }
+}
+
+// A lone `*` matches every scope stack
+fn is_universal(matcher: &dyn Matcher) -> bool {
+    alternatives(matcher).into_iter().any(|alternative| match conjuncts(alternative).as_slice() {
+        [(true, conjunct)] => match conjunct.node() {
+            MatcherNode::Path(_, [scope]) => segments(scope.as_ref()).is_some_and(|segments| segments == [None]),
+            _ => false,
+        },
+        _ => false,
+    })
+}
@@ -0,0 +1,85 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::matchers::{alternatives, any_of, Matcher, MatcherNode};
+use crate::scope::ScopeStackLike;
+use crate::{LineCol, ParseError};
+
+// Error from `parse_many`, naming the item that failed to parse
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseManyError {
+    pub index: usize,
+    pub error: ParseError<LineCol>,
+}
+
+impl fmt::Display for ParseManyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "selector {}: {}", self.index, self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseManyError {}
+
+// SelectorList, one Or selector built from several source items
+pub struct SelectorList {
+    matcher: Option<Box<dyn Matcher>>,
+    sources: Vec<usize>,
+}
+
+impl SelectorList {
+    pub fn new(items: Vec<Box<dyn Matcher>>) -> Self {
+        let sources = items
+            .iter()
+            .enumerate()
+            .flat_map(|(index, item)| core::iter::repeat_n(index, alternatives(item.as_ref()).len()))
+            .collect();
+
+        Self {
+            matcher: any_of(items),
+            sources,
+        }
+    }
+
+    // The combined selector, or `None` for an empty list
+    pub fn matcher(&self) -> Option<&dyn Matcher> {
+        self.matcher.as_deref()
+    }
+
+    // Source item of each alternative, in the order of `matchers::alternatives`
+    pub fn sources(&self) -> &[usize] {
+        &self.sources
+    }
+
+    // Source item of the first alternative matching the scopes
+    pub fn source_of(&self, scopes: &str) -> Option<usize> {
+        let matcher = self.matcher.as_deref()?;
+        alternatives(matcher)
+            .into_iter()
+            .zip(self.sources.iter())
+            .find(|(alternative, _)| alternative.matches(scopes))
+            .map(|(_, &source)| source)
+    }
+}
+
+impl Matcher for SelectorList {
+    fn matches(&self, scopes: &str) -> bool {
+        self.matcher.as_ref().is_some_and(|matcher| matcher.matches(scopes))
+    }
+
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        self.matcher.as_ref().is_some_and(|matcher| matcher.matches_stack(scopes))
+    }
+
+    fn get_prefix(&self, scopes: &str) -> Option<char> {
+        self.matcher.as_ref().and_then(|matcher| matcher.get_prefix(scopes))
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        match &self.matcher {
+            Some(matcher) => matcher.node(),
+            None => MatcherNode::Opaque,
+        }
+    }
+}
@@ -1,25 +1,358 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::scope::{ScopeLike, ScopeStackLike};
+
 // Traits for matchers
 pub trait Matcher {
     fn matches(&self, scope: &str) -> bool;
+    // Matches a scope stack of any representation, joining it into a string
+    // unless the matcher visits it directly
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        self.matches(&scopes.scopes_string())
+    }
+    // Matches a single scope of any representation, as `matches_stack` does
+    fn matches_scope(&self, scope: &dyn ScopeLike) -> bool {
+        self.matches(&scope.scope_name())
+    }
     fn get_prefix(&self, _scopes: &str) -> Option<char> {
         None
     }
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Opaque
+    }
+}
+
+// Structural view of a matcher, for code that inspects parsed selectors
+#[non_exhaustive]
+pub enum MatcherNode<'a> {
+    Segment(&'a str),
+    SegmentIgnoringCase(&'a str),
+    Glob(&'a str),
+    #[cfg(feature = "regex")]
+    Regex(&'a str),
+    // A host-defined predicate, by name with its sigil
+    Predicate(&'a str),
+    Wildcard,
+    Scope(&'a [Box<dyn Matcher>]),
+    Path(Option<char>, &'a [Box<dyn Matcher>]),
+    Group(Option<char>, &'a dyn Matcher),
+    Or(&'a dyn Matcher, &'a dyn Matcher),
+    And(&'a dyn Matcher, &'a dyn Matcher),
+    Negate(&'a dyn Matcher),
+    Opaque,
+}
+
+// Alternatives of the top-level `,` and `|` operators, looking through groups
+pub fn alternatives(matcher: &dyn Matcher) -> Vec<&dyn Matcher> {
+    fn collect<'a>(matcher: &'a dyn Matcher, alternatives: &mut Vec<&'a dyn Matcher>) {
+        match matcher.node() {
+            MatcherNode::Or(left, right) => {
+                collect(left, alternatives);
+                collect(right, alternatives);
+            }
+            MatcherNode::Group(_, selector) => collect(selector, alternatives),
+            _ => alternatives.push(matcher),
+        }
+    }
+
+    let mut alternatives = Vec::new();
+    collect(matcher, &mut alternatives);
+    alternatives
+}
+
+// Or selector of several matchers, the inverse of `alternatives`
+pub fn any_of(matchers: Vec<Box<dyn Matcher>>) -> Option<Box<dyn Matcher>> {
+    matchers
+        .into_iter()
+        .rev()
+        .reduce(|right, left| Box::new(OrMatcher::new(left, right)))
+}
+
+// Operands of the `&` and `-` operators, with `false` marking negated ones
+pub fn conjuncts(matcher: &dyn Matcher) -> Vec<(bool, &dyn Matcher)> {
+    fn collect<'a>(matcher: &'a dyn Matcher, positive: bool, conjuncts: &mut Vec<(bool, &'a dyn Matcher)>) {
+        match matcher.node() {
+            MatcherNode::And(left, right) if positive => {
+                collect(left, true, conjuncts);
+                collect(right, true, conjuncts);
+            }
+            MatcherNode::Negate(negated) if positive => collect(negated, false, conjuncts),
+            MatcherNode::Group(_, selector) if alternatives(selector).len() == 1 => {
+                collect(selector, positive, conjuncts)
+            }
+            _ => conjuncts.push((positive, matcher)),
+        }
+    }
+
+    let mut conjuncts = Vec::new();
+    collect(matcher, true, &mut conjuncts);
+    conjuncts
+}
+
+// Canonical selector syntax, parsing back to an equivalent matcher
+impl fmt::Display for dyn Matcher + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.node() {
+            MatcherNode::Or(left, right) => write!(f, "{}, {}", left, right),
+            _ => fmt_composite(self, f),
+        }
+    }
+}
+
+fn fmt_composite(matcher: &dyn Matcher, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match matcher.node() {
+        MatcherNode::And(left, right) => {
+            fmt_expression(left, f)?;
+            match right.node() {
+                MatcherNode::Negate(negated) => {
+                    f.write_str(" - ")?;
+                    fmt_composite(negated, f)
+                }
+                _ => {
+                    f.write_str(" & ")?;
+                    fmt_composite(right, f)
+                }
+            }
+        }
+        MatcherNode::Or(left, right) => {
+            fmt_expression(left, f)?;
+            f.write_str(" | ")?;
+            fmt_composite(right, f)
+        }
+        _ => fmt_expression(matcher, f),
+    }
+}
+
+fn fmt_expression(matcher: &dyn Matcher, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match matcher.node() {
+        MatcherNode::Segment(segment)
+        | MatcherNode::SegmentIgnoringCase(segment)
+        | MatcherNode::Glob(segment) => f.write_str(segment),
+        #[cfg(feature = "regex")]
+        MatcherNode::Regex(pattern) => write!(f, "/{}/", pattern),
+        MatcherNode::Predicate(name) => f.write_str(name),
+        MatcherNode::Wildcard => f.write_str("*"),
+        MatcherNode::Scope(segments) => fmt_joined(segments, ".", f),
+        MatcherNode::Path(prefix, scopes) => {
+            fmt_prefix(prefix, f)?;
+            fmt_joined(scopes, " ", f)
+        }
+        MatcherNode::Group(prefix, selector) => {
+            fmt_prefix(prefix, f)?;
+            write!(f, "({})", selector)
+        }
+        MatcherNode::Negate(negated) => match negated.node() {
+            MatcherNode::Path(..) | MatcherNode::Group(..) => {
+                f.write_str("-")?;
+                fmt_expression(negated, f)
+            }
+            _ => write!(f, "-({})", negated),
+        },
+        MatcherNode::Or(..) | MatcherNode::And(..) => write!(f, "({})", matcher),
+        MatcherNode::Opaque => f.write_str("?"),
+    }
+}
+
+fn fmt_joined(matchers: &[Box<dyn Matcher>], separator: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (index, matcher) in matchers.iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+        fmt_expression(matcher.as_ref(), f)?;
+    }
+    Ok(())
+}
+
+fn fmt_prefix(prefix: Option<char>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match prefix {
+        Some(prefix) => write!(f, "{}:", prefix),
+        None => Ok(()),
+    }
 }
 
 // SegmentMatcher
 pub struct SegmentMatcher {
     segment: String,
+    ignore_case: bool,
 }
 
 impl SegmentMatcher {
     pub fn new(segment: &str) -> Self {
-        Self { segment: segment.to_string() }
+        Self {
+            segment: segment.to_string(),
+            ignore_case: false,
+        }
+    }
+
+    pub fn ignoring_case(segment: &str) -> Self {
+        Self {
+            segment: segment.to_string(),
+            ignore_case: true,
+        }
     }
 }
 
 impl Matcher for SegmentMatcher {
     fn matches(&self, scope: &str) -> bool {
-        scope == self.segment
+        if self.ignore_case {
+            scope
+                .chars()
+                .flat_map(char::to_lowercase)
+                .eq(self.segment.chars().flat_map(char::to_lowercase))
+        } else {
+            scope == self.segment
+        }
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        if self.ignore_case {
+            MatcherNode::SegmentIgnoringCase(&self.segment)
+        } else {
+            MatcherNode::Segment(&self.segment)
+        }
+    }
+}
+
+// GlobSegmentMatcher
+pub struct GlobSegmentMatcher {
+    pattern: String,
+    ignore_case: bool,
+}
+
+impl GlobSegmentMatcher {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            ignore_case: false,
+        }
+    }
+
+    pub fn ignoring_case(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            ignore_case: true,
+        }
+    }
+}
+
+impl Matcher for GlobSegmentMatcher {
+    fn matches(&self, scope: &str) -> bool {
+        if self.ignore_case {
+            let pattern: Vec<char> = self.pattern.chars().flat_map(char::to_lowercase).collect();
+            let scope: Vec<char> = scope.chars().flat_map(char::to_lowercase).collect();
+            glob_matches(&pattern, &scope)
+        } else {
+            let pattern: Vec<char> = self.pattern.chars().collect();
+            let scope: Vec<char> = scope.chars().collect();
+            glob_matches(&pattern, &scope)
+        }
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Glob(&self.pattern)
+    }
+}
+
+// `*` matches any run of characters and `?` exactly one, backtracking only to
+// the most recent `*`
+fn glob_matches(pattern: &[char], scope: &[char]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while s < scope.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, s));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == scope[s] => {
+                p += 1;
+                s += 1;
+            }
+            _ => match star {
+                Some((star_p, star_s)) => {
+                    star = Some((star_p, star_s + 1));
+                    p = star_p + 1;
+                    s = star_s + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// RegexSegmentMatcher
+#[cfg(feature = "regex")]
+pub struct RegexSegmentMatcher {
+    pattern: String,
+    regex: regex::Regex,
+}
+
+#[cfg(feature = "regex")]
+impl RegexSegmentMatcher {
+    // Compiles a pattern that has to match a whole segment
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Self::build(pattern, false)
+    }
+
+    pub fn ignoring_case(pattern: &str) -> Result<Self, regex::Error> {
+        Self::build(pattern, true)
+    }
+
+    fn build(pattern: &str, ignore_case: bool) -> Result<Self, regex::Error> {
+        let regex = regex::RegexBuilder::new(&alloc::format!("^(?:{})$", pattern))
+            .case_insensitive(ignore_case)
+            .build()?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Matcher for RegexSegmentMatcher {
+    fn matches(&self, scope: &str) -> bool {
+        self.regex.is_match(scope)
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Regex(&self.pattern)
+    }
+}
+
+// PredicateMatcher, a host-defined atom such as `@readonly`. Like a segment
+// it is given the scope segment at its position
+pub struct PredicateMatcher {
+    name: String,
+    matcher: Box<dyn Matcher>,
+}
+
+impl PredicateMatcher {
+    pub fn new(name: &str, matcher: Box<dyn Matcher>) -> Self {
+        Self {
+            name: name.to_string(),
+            matcher,
+        }
+    }
+}
+
+impl Matcher for PredicateMatcher {
+    fn matches(&self, scope: &str) -> bool {
+        self.matcher.matches(scope)
+    }
+
+    fn matches_scope(&self, scope: &dyn ScopeLike) -> bool {
+        self.matcher.matches_scope(scope)
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Predicate(&self.name)
     }
 }
 
@@ -30,6 +363,18 @@ impl Matcher for TrueMatcher {
     fn matches(&self, _: &str) -> bool {
         true
     }
+
+    fn matches_stack(&self, _: &dyn ScopeStackLike) -> bool {
+        true
+    }
+
+    fn matches_scope(&self, _: &dyn ScopeLike) -> bool {
+        true
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Wildcard
+    }
 }
 
 // ScopeMatcher
@@ -38,7 +383,7 @@ pub struct ScopeMatcher {
 }
 
 impl ScopeMatcher {
-    pub fn new(segments: Vec<Box<(dyn Matcher + 'static)>>) -> Self {
+    pub fn new(segments: Vec<Box<dyn Matcher>>) -> Self {
         Self { segments }
     }
 }
@@ -54,6 +399,24 @@ impl Matcher for ScopeMatcher {
             segment.matches(scope)
         })
     }
+
+    fn matches_scope(&self, scope: &dyn ScopeLike) -> bool {
+        let mut index = 0;
+        let mut matched = true;
+        scope.for_each_segment(&mut |segment| {
+            if let Some(matcher) = self.segments.get(index) {
+                matched = matcher.matches(segment);
+            }
+            index += 1;
+            matched && index < self.segments.len()
+        });
+
+        matched && index >= self.segments.len()
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Scope(&self.segments)
+    }
 }
 
 // GroupMatcher
@@ -76,6 +439,10 @@ impl Matcher for GroupMatcher {
         self.selector.matches(scopes)
     }
 
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        self.selector.matches_stack(scopes)
+    }
+
     fn get_prefix(&self, scopes: &str) -> Option<char> {
         if self.matches(scopes) {
             self.prefix
@@ -83,6 +450,10 @@ impl Matcher for GroupMatcher {
             None
         }
     }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Group(self.prefix, self.selector.as_ref())
+    }
 }
 
 // PathMatcher
@@ -127,6 +498,22 @@ impl Matcher for PathMatcher {
         false
     }
 
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        if self.matchers.is_empty() {
+            return false;
+        }
+
+        let mut index = 0;
+        scopes.for_each_scope(&mut |scope| {
+            if self.matchers[index].matches_scope(scope) {
+                index += 1;
+            }
+            index < self.matchers.len()
+        });
+
+        index >= self.matchers.len()
+    }
+
     fn get_prefix(&self, scopes: &str) -> Option<char> {
         if self.matches(scopes) {
             self.prefix
@@ -134,6 +521,10 @@ impl Matcher for PathMatcher {
             None
         }
     }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Path(self.prefix, &self.matchers)
+    }
 }
 
 // OrMatcher
@@ -153,9 +544,17 @@ impl Matcher for OrMatcher {
         self.left.matches(scopes) || self.right.matches(scopes)
     }
 
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        self.left.matches_stack(scopes) || self.right.matches_stack(scopes)
+    }
+
     fn get_prefix(&self, scopes: &str) -> Option<char> {
         self.left.get_prefix(scopes).or_else(|| self.right.get_prefix(scopes))
     }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Or(self.left.as_ref(), self.right.as_ref())
+    }
 }
 
 // AndMatcher
@@ -175,6 +574,10 @@ impl Matcher for AndMatcher {
         self.left.matches(scopes) && self.right.matches(scopes)
     }
 
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        self.left.matches_stack(scopes) && self.right.matches_stack(scopes)
+    }
+
     fn get_prefix(&self, scopes: &str) -> Option<char> {
         if self.matches(scopes) {
             self.left.get_prefix(scopes)
@@ -182,6 +585,10 @@ impl Matcher for AndMatcher {
             None
         }
     }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::And(self.left.as_ref(), self.right.as_ref())
+    }
 }
 
 // NegateMatcher
@@ -199,6 +606,14 @@ impl Matcher for NegateMatcher {
     fn matches(&self, scopes: &str) -> bool {
         !self.matcher.matches(scopes)
     }
+
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        !self.matcher.matches_stack(scopes)
+    }
+
+    fn node(&self) -> MatcherNode<'_> {
+        MatcherNode::Negate(self.matcher.as_ref())
+    }
 }
 
 // CompositeMatcher
@@ -227,7 +642,15 @@ impl Matcher for CompositeMatcher {
         self.matcher.matches(scopes)
     }
 
+    fn matches_stack(&self, scopes: &dyn ScopeStackLike) -> bool {
+        self.matcher.matches_stack(scopes)
+    }
+
     fn get_prefix(&self, scopes: &str) -> Option<char> {
         self.matcher.get_prefix(scopes)
     }
-}
\ No newline at end of file
+
+    fn node(&self) -> MatcherNode<'_> {
+        self.matcher.node()
+    }
+}
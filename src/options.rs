@@ -0,0 +1,181 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::matchers::Matcher;
+use crate::{LineCol, ParseError};
+
+// ParseOptions, opt-in extensions to the standard selector grammar
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    // Accept Unicode identifier characters in segments, besides `[a-zA-Z0-9_+-]`
+    pub unicode_segments: bool,
+    // Compare segments with scopes ignoring case
+    pub case_insensitive: bool,
+    // Accept `*` and `?` wildcards within a segment, as in `meta.*tag*`
+    pub glob_segments: bool,
+    // Accept `/…/` regular expressions as segments, as in `source./(js|ts)x?/`;
+    // they need the `regex` feature
+    pub regex_segments: bool,
+    // Host-defined `@name` and `$name` atoms, as in `variable @readonly`
+    pub predicates: Predicates,
+    pub mode: ParseMode,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    // The grammar as it always was, dropping a trailing `,`
+    #[default]
+    Lenient,
+    // Rejects input that other implementations read differently or that is
+    // likely a mistake, see `StrictIssue`
+    Strict,
+}
+
+impl ParseOptions {
+    // The strict ASCII grammar with case-sensitive matching used by `parse`
+    pub const DEFAULT: ParseOptions = ParseOptions {
+        unicode_segments: false,
+        case_insensitive: false,
+        glob_segments: false,
+        regex_segments: false,
+        predicates: Predicates::new(),
+        mode: ParseMode::Lenient,
+    };
+}
+
+// Shared, so that options can be sent to and used from other threads
+type Factory = Arc<dyn Fn() -> Box<dyn Matcher> + Send + Sync>;
+
+// Predicate names, with their sigil, and the factories building their matchers
+#[derive(Clone, Default)]
+pub struct Predicates {
+    factories: Vec<(String, Factory)>,
+}
+
+impl Predicates {
+    pub const fn new() -> Self {
+        Self { factories: Vec::new() }
+    }
+
+    // Registers `name`, such as `@readonly`, replacing an earlier registration;
+    // each occurrence in a selector gets its own matcher from `factory`
+    pub fn register(&mut self, name: &str, factory: impl Fn() -> Box<dyn Matcher> + Send + Sync + 'static) {
+        let factory: Factory = Arc::new(factory);
+        match self.factories.iter_mut().find(|(registered, _)| registered == name) {
+            Some(entry) => entry.1 = factory,
+            None => self.factories.push((name.to_string(), factory)),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.iter().any(|(registered, _)| registered == name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub(crate) fn build(&self, name: &str) -> Option<Box<dyn Matcher>> {
+        self.factories
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, factory)| factory())
+    }
+}
+
+impl fmt::Debug for Predicates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.names()).finish()
+    }
+}
+
+// Registries are equal when they map the same names to the same factories
+impl PartialEq for Predicates {
+    fn eq(&self, other: &Self) -> bool {
+        self.factories.len() == other.factories.len()
+            && self.factories.iter().zip(&other.factories).all(|(left, right)| {
+                left.0 == right.0 && Arc::ptr_eq(&left.1, &right.1)
+            })
+    }
+}
+
+impl Eq for Predicates {}
+
+// Error from `parse_with_options`
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectorError {
+    // The selector does not follow the grammar
+    Syntax(ParseError<LineCol>),
+    // An `@name` or `$name` atom that is not in `ParseOptions::predicates`
+    UnknownPredicate { name: String, location: LineCol },
+    // A `/…/` segment that does not compile, with the `regex` crate's message;
+    // the location is the opening `/`
+    InvalidRegex { message: String, location: LineCol },
+    // Input that `ParseMode::Strict` rejects
+    Strict { issue: StrictIssue, location: LineCol },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrictIssue {
+    // A `,` with nothing after it, as in `a,`
+    TrailingComma,
+    // Nothing before a `,`, as in `a,,b` or `,a`
+    EmptyAlternative,
+    // `L:`, `R:` or `B:` other than at the start of an injection selector's
+    // alternative, as in `a - L:b` or `(R:a)`
+    StrayPrefix,
+    // A segment ending with `-`, as in `a- b`, `a-.b` or `a*- b`, which reads
+    // as a subtraction but parses as part of the segment. A `-` within a
+    // segment, as in `a-b` or `meta.property-name`, is not ambiguous: every
+    // implementation reads it as part of the segment, and scope names use it
+    // throughout. Neither is an unspaced operator, as in `a -b` or `(a)-b`,
+    // as a segment cannot start with `-`
+    AmbiguousHyphen,
+}
+
+impl fmt::Display for StrictIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StrictIssue::TrailingComma => "trailing `,` without an alternative after it",
+            StrictIssue::EmptyAlternative => "empty alternative before `,`",
+            StrictIssue::StrayPrefix => "prefix outside the start of an injection selector's alternative",
+            StrictIssue::AmbiguousHyphen => "ambiguous `-`, write a subtraction as `a - b`",
+        })
+    }
+}
+
+impl SelectorError {
+    pub fn location(&self) -> LineCol {
+        match self {
+            SelectorError::Syntax(err) => err.location,
+            SelectorError::UnknownPredicate { location, .. }
+            | SelectorError::InvalidRegex { location, .. }
+            | SelectorError::Strict { location, .. } => *location,
+        }
+    }
+}
+
+impl From<ParseError<LineCol>> for SelectorError {
+    fn from(err: ParseError<LineCol>) -> Self {
+        SelectorError::Syntax(err)
+    }
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorError::Syntax(err) => write!(f, "{}", err),
+            SelectorError::UnknownPredicate { name, location } => {
+                write!(f, "error at {}: unknown predicate `{}`", location, name)
+            }
+            SelectorError::InvalidRegex { message, location } => write!(f, "error at {}: {}", location, message),
+            SelectorError::Strict { issue, location } => write!(f, "error at {}: {}", location, issue),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SelectorError {}
@@ -0,0 +1,92 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::matchers::Matcher;
+use crate::{LineCol, ParseError};
+
+create_exception!(
+    textmate_scope_selector_peg,
+    SelectorError,
+    PyValueError,
+    "Raised when a scope selector fails to parse, with `line`, `column`, `offset` and `expected` attributes."
+);
+
+// Scopes given as a space-separated stack or as a list of scopes
+#[derive(FromPyObject)]
+enum Scopes {
+    Stack(String),
+    List(Vec<String>),
+}
+
+impl Scopes {
+    fn join(self) -> String {
+        match self {
+            Scopes::Stack(scopes) => scopes,
+            Scopes::List(scopes) => scopes.join(" "),
+        }
+    }
+}
+
+// Selector
+#[pyclass(name = "Selector", module = "textmate_scope_selector_peg", unsendable)]
+pub struct Selector {
+    matcher: Box<dyn Matcher>,
+}
+
+#[pymethods]
+impl Selector {
+    fn matches(&self, scopes: Scopes) -> bool {
+        self.matcher.matches(&scopes.join())
+    }
+
+    fn get_prefix(&self, scopes: Scopes) -> Option<char> {
+        self.matcher.get_prefix(&scopes.join())
+    }
+
+    fn __str__(&self) -> String {
+        self.matcher.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Selector({:?})", self.matcher.to_string())
+    }
+}
+
+fn selector_error(py: Python<'_>, err: ParseError<LineCol>) -> PyErr {
+    let error = SelectorError::new_err(err.to_string());
+    let value = error.value(py);
+    let expected: Vec<&str> = err.expected.tokens().collect();
+
+    let attributes = [
+        value.setattr("line", err.location.line),
+        value.setattr("column", err.location.column),
+        value.setattr("offset", err.location.offset),
+        value.setattr("expected", expected),
+    ];
+    match attributes.into_iter().find_map(Result::err) {
+        Some(err) => err,
+        None => error,
+    }
+}
+
+#[pyfunction]
+fn parse(py: Python<'_>, selector: &str) -> PyResult<Selector> {
+    match crate::parse(selector) {
+        Ok(matcher) => Ok(Selector { matcher }),
+        Err(err) => Err(selector_error(py, err)),
+    }
+}
+
+#[pymodule]
+pub fn textmate_scope_selector_peg(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_class::<Selector>()?;
+    m.add("SelectorError", m.py().get_type::<SelectorError>())?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::matchers::{any_of, Matcher};
+use crate::{LineCol, ParseError};
+
+// Characters the grammar skips as whitespace
+pub(crate) const WHITESPACE: &[char] = &[' ', '\t', '\n', '\r'];
+
+// Recovered, the part of a selector that parsed and the errors in the rest
+pub struct Recovered<E = ParseError<LineCol>> {
+    pub selector: Option<Box<dyn Matcher>>,
+    pub parsed: usize,
+    pub errors: Vec<E>,
+}
+
+impl<E> Recovered<E> {
+    pub(crate) fn new(alternatives: Vec<Box<dyn Matcher>>, errors: Vec<E>) -> Self {
+        Self {
+            parsed: alternatives.len(),
+            selector: any_of(alternatives),
+            errors,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+pub(crate) enum Chunk<'a> {
+    Alternative(&'a str),
+    // Only whitespace after the last comma
+    Trailing,
+}
+
+// Parses each alternative with `parse`, given its byte offset, which returns
+// `None` to skip it
+pub(crate) fn recover<E>(
+    source: &str,
+    regex: bool,
+    mut parse: impl FnMut(Chunk<'_>, usize) -> Option<Result<Box<dyn Matcher>, E>>,
+) -> Recovered<E> {
+    let mut alternatives = Vec::new();
+    let mut errors = Vec::new();
+
+    let chunks = split_alternatives(source, regex);
+    let last = chunks.len() - 1;
+    for (index, (start, chunk)) in chunks.into_iter().enumerate() {
+        let chunk = if index == last && index > 0 && chunk.trim_matches(WHITESPACE).is_empty() {
+            Chunk::Trailing
+        } else {
+            Chunk::Alternative(chunk)
+        };
+        match parse(chunk, start) {
+            Some(Ok(matcher)) => alternatives.push(matcher),
+            Some(Err(err)) => errors.push(err),
+            None => {}
+        }
+    }
+
+    Recovered::new(alternatives, errors)
+}
+
+// Splits a selector at the commas outside of parentheses, and outside of
+// `/…/` segments when `regex` is set, keeping byte offsets. A `(` that is
+// never closed does not open a group, so one typo does not swallow the
+// alternatives after it
+pub(crate) fn split_alternatives(source: &str, regex: bool) -> Vec<(usize, &str)> {
+    let structure = structure(source, regex);
+    let mut open = Vec::new();
+    for &(offset, c) in &structure {
+        match c {
+            '(' => open.push(offset),
+            ')' => {
+                open.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (offset, c) in structure {
+        match c {
+            '(' if !open.contains(&offset) => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                chunks.push((start, &source[start..offset]));
+                start = offset + 1;
+            }
+            _ => {}
+        }
+    }
+
+    chunks.push((start, &source[start..]));
+    chunks
+}
+
+// Characters with their offsets, skipping the `/…/` segments when `regex` is set
+fn structure(source: &str, regex: bool) -> Vec<(usize, char)> {
+    let mut structure = Vec::new();
+    let mut skip_to = 0;
+    for (offset, c) in source.char_indices() {
+        if offset < skip_to {
+            continue;
+        }
+        if let Some(length) = regex.then(|| regex_length(&source[offset..])).flatten() {
+            skip_to = offset + length;
+            continue;
+        }
+        structure.push((offset, c));
+    }
+    structure
+}
+
+// Length of a `/…/` segment at the start of `source`, as the grammar reads it:
+// a pattern on one line, where `\/` stands for `/`
+fn regex_length(source: &str) -> Option<usize> {
+    let mut chars = source.strip_prefix('/')?.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '/' if offset > 0 => return Some(offset + 2),
+            '\n' | '\r' | '/' => return None,
+            '\\' => {
+                if let Some((_, '\n' | '\r')) | None = chars.next() {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
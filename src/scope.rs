@@ -0,0 +1,108 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// A scope whose segments can be visited without building a string, such as an
+// interned scope handle
+pub trait ScopeLike {
+    // Calls `visit` with each segment in order, until it returns `false`
+    fn for_each_segment(&self, visit: &mut dyn FnMut(&str) -> bool);
+
+    // The dotted scope name, for matchers that only work on strings
+    fn scope_name(&self) -> String {
+        let mut name = String::new();
+        self.for_each_segment(&mut |segment| {
+            if !name.is_empty() {
+                name.push('.');
+            }
+            name.push_str(segment);
+            true
+        });
+        name
+    }
+}
+
+// A scope stack whose scopes can be visited, outermost first
+pub trait ScopeStackLike {
+    // Calls `visit` with each scope in order, until it returns `false`
+    fn for_each_scope(&self, visit: &mut dyn FnMut(&dyn ScopeLike) -> bool);
+
+    // The space-separated scope stack, for matchers that only work on strings
+    fn scopes_string(&self) -> String {
+        let mut scopes = String::new();
+        let mut first = true;
+        self.for_each_scope(&mut |scope| {
+            if !first {
+                scopes.push(' ');
+            }
+            first = false;
+            scopes.push_str(&scope.scope_name());
+            true
+        });
+        scopes
+    }
+}
+
+// A dotted scope name
+impl ScopeLike for &str {
+    fn for_each_segment(&self, visit: &mut dyn FnMut(&str) -> bool) {
+        for segment in self.split('.') {
+            if !visit(segment) {
+                break;
+            }
+        }
+    }
+}
+
+impl ScopeLike for String {
+    fn for_each_segment(&self, visit: &mut dyn FnMut(&str) -> bool) {
+        self.as_str().for_each_segment(visit)
+    }
+}
+
+// Segments of a scope, already split at the dots. A bare `&[&str]` is a scope
+// stack, one scope per element
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segments<'a>(pub &'a [&'a str]);
+
+impl ScopeLike for Segments<'_> {
+    fn for_each_segment(&self, visit: &mut dyn FnMut(&str) -> bool) {
+        for segment in self.0 {
+            if !visit(segment) {
+                break;
+            }
+        }
+    }
+}
+
+// A space-separated scope stack
+impl ScopeStackLike for &str {
+    fn for_each_scope(&self, visit: &mut dyn FnMut(&dyn ScopeLike) -> bool) {
+        for scope in self.split(' ') {
+            if !visit(&scope) {
+                break;
+            }
+        }
+    }
+}
+
+impl ScopeStackLike for String {
+    fn for_each_scope(&self, visit: &mut dyn FnMut(&dyn ScopeLike) -> bool) {
+        self.as_str().for_each_scope(visit)
+    }
+}
+
+impl<T: ScopeLike> ScopeStackLike for &[T] {
+    fn for_each_scope(&self, visit: &mut dyn FnMut(&dyn ScopeLike) -> bool) {
+        for scope in self.iter() {
+            if !visit(scope) {
+                break;
+            }
+        }
+    }
+}
+
+impl<T: ScopeLike> ScopeStackLike for Vec<T> {
+    fn for_each_scope(&self, visit: &mut dyn FnMut(&dyn ScopeLike) -> bool) {
+        self.as_slice().for_each_scope(visit)
+    }
+}
@@ -0,0 +1,194 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::matchers::Matcher;
+use crate::{LineCol, ParseError};
+
+peg::parser! {
+    grammar parser() for str {
+        rule identifier() -> &'input str
+            = $(['a'..='z' | 'A'..='Z' | '_'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-']*)
+
+        rule token_type() -> Option<String>
+            = "*" { None }
+            / token_type:identifier() { Some(token_type.to_string()) }
+
+        // `type.modifier:language`, with `*` standing for any type
+        pub rule selector() -> SemanticSelector
+            = token_type:token_type() modifiers:("." modifier:identifier() { modifier.to_string() })*
+              language:(":" language:identifier() { language.to_string() })? {
+                SemanticSelector { token_type, modifiers, language }
+            }
+    }
+}
+
+// Semantic token, as reported by a language server
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SemanticToken<'a> {
+    pub token_type: &'a str,
+    pub modifiers: &'a [&'a str],
+    pub language: Option<&'a str>,
+}
+
+// SemanticSelector, a key of a semantic token scope table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticSelector {
+    token_type: Option<String>,
+    modifiers: Vec<String>,
+    language: Option<String>,
+}
+
+impl SemanticSelector {
+    pub fn parse(selector: &str) -> Result<Self, ParseError<LineCol>> {
+        parser::selector(selector)
+    }
+
+    // Score of the selector for a token, or `None` if it does not apply; the
+    // type counts for 100, each modifier for 100 and the language for 10
+    pub fn score(&self, token: &SemanticToken<'_>) -> Option<u32> {
+        let mut score = 0;
+        if let Some(token_type) = &self.token_type {
+            if token_type != token.token_type {
+                return None;
+            }
+            score += 100;
+        }
+        for modifier in &self.modifiers {
+            if !token.modifiers.contains(&modifier.as_str()) {
+                return None;
+            }
+            score += 100;
+        }
+        if let Some(language) = &self.language {
+            if token.language != Some(language.as_str()) {
+                return None;
+            }
+            score += 10;
+        }
+        Some(score)
+    }
+}
+
+impl fmt::Display for SemanticSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.token_type.as_deref().unwrap_or("*"))?;
+        for modifier in &self.modifiers {
+            write!(f, ".{}", modifier)?;
+        }
+        match &self.language {
+            Some(language) => write!(f, ":{}", language),
+            None => Ok(()),
+        }
+    }
+}
+
+// Semantic token scopes from VS Code's token classification registry
+pub const VSCODE_DEFAULTS: &[(&str, &[&str])] = &[
+    ("comment", &["comment"]),
+    ("string", &["string"]),
+    ("keyword", &["keyword.control"]),
+    ("number", &["constant.numeric"]),
+    ("regexp", &["constant.regexp"]),
+    ("operator", &["keyword.operator"]),
+    ("namespace", &["entity.name.namespace"]),
+    ("type", &["entity.name.type", "support.type"]),
+    ("struct", &["entity.name.type.struct"]),
+    ("class", &["entity.name.type.class", "support.class"]),
+    ("interface", &["entity.name.type.interface"]),
+    ("enum", &["entity.name.type.enum"]),
+    ("typeParameter", &["entity.name.type.parameter"]),
+    ("function", &["entity.name.function", "support.function"]),
+    ("member", &["entity.name.function.member", "support.function"]),
+    ("method", &["entity.name.function.member", "support.function"]),
+    ("macro", &["entity.name.function.preprocessor"]),
+    ("variable", &["variable.other.readwrite", "entity.name.variable"]),
+    ("parameter", &["variable.parameter"]),
+    ("property", &["variable.other.property"]),
+    ("enumMember", &["variable.other.enummember"]),
+    ("event", &["variable.other.event"]),
+    ("decorator", &["entity.name.decorator", "entity.name.function"]),
+    ("label", &["entity.name.label"]),
+    ("variable.readonly", &["variable.other.constant"]),
+    ("property.readonly", &["variable.other.constant.property"]),
+    ("type.defaultLibrary", &["support.type"]),
+    ("class.defaultLibrary", &["support.class"]),
+    ("interface.defaultLibrary", &["support.class"]),
+    ("function.defaultLibrary", &["support.function"]),
+    ("method.defaultLibrary", &["support.function"]),
+    ("variable.defaultLibrary", &["support.variable"]),
+    ("property.defaultLibrary", &["support.variable.property"]),
+    ("variable.readonly.defaultLibrary", &["support.constant"]),
+    ("property.readonly.defaultLibrary", &["support.constant.property"]),
+];
+
+// SemanticScopeMap, semantic selectors mapped to TextMate scopes in order of preference
+#[derive(Clone, Debug, Default)]
+pub struct SemanticScopeMap {
+    rules: Vec<(SemanticSelector, Vec<String>)>,
+}
+
+impl SemanticScopeMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Loads a table such as the `semanticTokenScopes` of a VS Code extension
+    pub fn from_entries<'a>(
+        entries: impl IntoIterator<Item = (&'a str, &'a [&'a str])>,
+    ) -> Result<Self, ParseError<LineCol>> {
+        let mut map = Self::new();
+        for (selector, scopes) in entries {
+            map.insert(selector, scopes.iter().copied())?;
+        }
+        Ok(map)
+    }
+
+    pub fn vscode_defaults() -> Self {
+        Self::from_entries(VSCODE_DEFAULTS.iter().copied()).expect("the defaults are valid semantic selectors")
+    }
+
+    // Adds a mapping, replacing the scopes of an equal selector
+    pub fn insert<'a>(
+        &mut self,
+        selector: &str,
+        scopes: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), ParseError<LineCol>> {
+        let selector = SemanticSelector::parse(selector)?;
+        let scopes = scopes.into_iter().map(str::to_string).collect();
+        match self.rules.iter_mut().find(|(existing, _)| *existing == selector) {
+            Some((_, existing)) => *existing = scopes,
+            None => self.rules.push((selector, scopes)),
+        }
+        Ok(())
+    }
+
+    // Scopes of the highest scoring selector for a token, the earlier one on a tie
+    pub fn resolve(&self, token: &SemanticToken<'_>) -> Option<&[String]> {
+        let mut best: Option<(u32, &[String])> = None;
+        for (selector, scopes) in &self.rules {
+            if let Some(score) = selector.score(token) {
+                if best.is_none_or(|(best, _)| score > best) {
+                    best = Some((score, scopes));
+                }
+            }
+        }
+        best.map(|(_, scopes)| scopes)
+    }
+
+    // Whether a TextMate selector matches any of the token's scopes, each
+    // pushed onto the enclosing scope stack
+    pub fn matches(&self, matcher: &dyn Matcher, scopes: &str, token: &SemanticToken<'_>) -> bool {
+        self.resolve(token).is_some_and(|resolved| {
+            resolved.iter().any(|scope| {
+                let mut stack = String::from(scopes.trim_end());
+                if !stack.is_empty() {
+                    stack.push(' ');
+                }
+                stack.push_str(scope);
+                matcher.matches(&stack)
+            })
+        })
+    }
+}
@@ -0,0 +1,119 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::matchers::{alternatives, conjuncts, Matcher, MatcherNode};
+
+// Specificity of a selector, compared field by field like a theme does: the
+// deeper the scope the selector matched in the stack, then the longer the
+// innermost scope of the selector, then the more scopes, then the more
+// segments, not counting `*`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Specificity {
+    // Position in the stack, counting from 1, of the scope the last scope of
+    // the selector matched; 0 without a stack, as from `specificity`
+    pub depth: usize,
+    pub last_scope_segments: usize,
+    pub scopes: usize,
+    pub segments: usize,
+}
+
+// Specificity of the most specific alternative of a selector
+pub fn specificity(matcher: &dyn Matcher) -> Specificity {
+    alternatives(matcher)
+        .into_iter()
+        .map(alternative_specificity)
+        .max()
+        .unwrap_or_default()
+}
+
+// Specificity of the most specific alternative matching the scopes, if any.
+// A rule for an outer scope ranks below any rule for a scope inside it, as in
+// TextMate, so `comment` beats `source.js string` in `source.js string comment`
+pub fn rank(matcher: &dyn Matcher, scopes: &str) -> Option<Specificity> {
+    let stack: Vec<&str> = scopes.split(' ').collect();
+    rank_stack(matcher, scopes, &stack)
+}
+
+// Index of the rule a theme applies to the scopes: the most specific matching
+// rule, the later one on a tie as later rules overwrite earlier ones
+pub fn winner<'a>(matchers: impl IntoIterator<Item = &'a dyn Matcher>, scopes: &str) -> Option<usize> {
+    let stack: Vec<&str> = scopes.split(' ').collect();
+    let mut best: Option<(Specificity, usize)> = None;
+    for (index, matcher) in matchers.into_iter().enumerate() {
+        if let Some(specificity) = rank_stack(matcher, scopes, &stack) {
+            if best.is_none_or(|(best, _)| specificity >= best) {
+                best = Some((specificity, index));
+            }
+        }
+    }
+    best.map(|(_, index)| index)
+}
+
+// `stack` holds the scopes split at spaces
+fn rank_stack(matcher: &dyn Matcher, scopes: &str, stack: &[&str]) -> Option<Specificity> {
+    alternatives(matcher)
+        .into_iter()
+        .filter(|alternative| alternative.matches(scopes))
+        .map(|alternative| Specificity {
+            depth: depth(alternative, scopes, stack),
+            ..alternative_specificity(alternative)
+        })
+        .max()
+}
+
+// Deepest position the positive operands of a matching alternative reach
+fn depth(alternative: &dyn Matcher, scopes: &str, stack: &[&str]) -> usize {
+    conjuncts(alternative)
+        .into_iter()
+        .filter(|(positive, _)| *positive)
+        .filter_map(|(_, conjunct)| match conjunct.node() {
+            MatcherNode::Path(_, scopes) => path_depth(scopes, stack),
+            MatcherNode::Group(..) | MatcherNode::Or(..) => rank_stack(conjunct, scopes, stack).map(|rank| rank.depth),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+// Scopes before the last match as early as they can, as in `PathMatcher`,
+// leaving the last scope its deepest match
+fn path_depth(scopes: &[Box<dyn Matcher>], stack: &[&str]) -> Option<usize> {
+    let (last, before) = scopes.split_last()?;
+    let mut start = 0;
+    for scope in before {
+        start += stack[start..].iter().position(|element| scope.matches(element))? + 1;
+    }
+    let position = stack[start..].iter().rposition(|element| last.matches(element))?;
+    Some(start + position + 1)
+}
+
+// Negated operands never add to the specificity of an alternative
+fn alternative_specificity(alternative: &dyn Matcher) -> Specificity {
+    conjuncts(alternative)
+        .into_iter()
+        .filter(|(positive, _)| *positive)
+        .map(|(_, conjunct)| match conjunct.node() {
+            MatcherNode::Path(_, scopes) => Specificity {
+                depth: 0,
+                last_scope_segments: scopes.last().map_or(0, |scope| scope_segments(scope.as_ref())),
+                scopes: scopes.len(),
+                segments: scopes.iter().map(|scope| scope_segments(scope.as_ref())).sum(),
+            },
+            MatcherNode::Group(..) | MatcherNode::Or(..) => specificity(conjunct),
+            _ => Specificity::default(),
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+// Wildcard segments match anything, so only the others count
+fn scope_segments(scope: &dyn Matcher) -> usize {
+    match scope.node() {
+        MatcherNode::Scope(segments) => segments
+            .iter()
+            .filter(|segment| !matches!(segment.node(), MatcherNode::Wildcard))
+            .count(),
+        MatcherNode::Wildcard => 0,
+        _ => 1,
+    }
+}
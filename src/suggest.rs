@@ -0,0 +1,56 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::specificity::{specificity, Specificity};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub selector: String,
+    pub specificity: Specificity,
+}
+
+// Selectors for the innermost scope of a stack, most specific first; every one
+// parses with `parse` and matches the stack
+pub fn suggest(scopes: &str) -> Vec<Suggestion> {
+    let stack: Vec<&str> = scopes.split(' ').filter(|scope| !scope.is_empty()).collect();
+    let Some((innermost, ancestors)) = stack.split_last() else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<String> = Vec::new();
+    if !ancestors.is_empty() {
+        candidates.push(stack.join(" "));
+    }
+    for target in prefixes(innermost) {
+        for ancestor in ancestors {
+            for context in prefixes(ancestor) {
+                candidates.push([context, " ", target].concat());
+            }
+        }
+        candidates.push(target.to_string());
+    }
+
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for candidate in candidates {
+        if suggestions.iter().any(|suggestion| suggestion.selector == candidate) {
+            continue;
+        }
+        match crate::parse(&candidate) {
+            Ok(matcher) if matcher.matches(scopes) => suggestions.push(Suggestion {
+                specificity: specificity(matcher.as_ref()),
+                selector: candidate,
+            }),
+            _ => {}
+        }
+    }
+
+    // Stable, so equally specific suggestions keep their order
+    suggestions.sort_by_key(|suggestion| core::cmp::Reverse(suggestion.specificity));
+    suggestions
+}
+
+// `a.b.c`, `a.b` and `a`
+fn prefixes(scope: &str) -> impl Iterator<Item = &str> {
+    let ends = scope.match_indices('.').map(|(index, _)| index).rev();
+    core::iter::once(scope).chain(ends.map(move |end| &scope[..end]))
+}
@@ -0,0 +1,109 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+// Byte range in the parsed selector
+pub type Span = Range<usize>;
+
+// Selector syntax tree the grammar produces, as from `parse_spanned`, every
+// node with its span
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node<'a> {
+    // Scopes separated by whitespace, as in `L:source.js string`
+    Path {
+        prefix: Option<Prefix>,
+        scopes: Vec<Scope<'a>>,
+        span: Span,
+    },
+    Group {
+        prefix: Option<Prefix>,
+        open: Span,
+        selector: Box<Node<'a>>,
+        close: Span,
+        span: Span,
+    },
+    // `-` before an operand; `operator` is the span of the `-`
+    Negate {
+        operator: Span,
+        operand: Box<Node<'a>>,
+        span: Span,
+    },
+    // `|`, `&`, `-` or `,` between two operands
+    Binary {
+        left: Box<Node<'a>>,
+        operator: Operator,
+        right: Box<Node<'a>>,
+        span: Span,
+    },
+}
+
+// `L:`, `R:` or `B:`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Prefix {
+    pub side: char,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operator {
+    pub symbol: char,
+    pub span: Span,
+}
+
+// Dotted scope, as in `string.quoted`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scope<'a> {
+    pub segments: Vec<Segment<'a>>,
+    pub span: Span,
+}
+
+// Segment as written, including `*`, globs, `/…/` patterns and predicates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub text: &'a str,
+    pub span: Span,
+}
+
+impl<'a> Node<'a> {
+    pub fn span(&self) -> Span {
+        match self {
+            Node::Path { span, .. } | Node::Group { span, .. } => span.clone(),
+            Node::Negate { span, .. } | Node::Binary { span, .. } => span.clone(),
+        }
+    }
+
+    // Segment covering a byte offset, with its scope, as for hover
+    pub fn segment_at(&self, offset: usize) -> Option<(&Scope<'a>, &Segment<'a>)> {
+        if !self.span().contains(&offset) {
+            return None;
+        }
+        match self {
+            Node::Path { scopes, .. } => scopes.iter().find_map(|scope| {
+                let segment = scope.segments.iter().find(|segment| segment.span.contains(&offset))?;
+                Some((scope, segment))
+            }),
+            Node::Group { selector, .. } => selector.segment_at(offset),
+            Node::Negate { operand, .. } => operand.segment_at(offset),
+            Node::Binary { left, right, .. } => left.segment_at(offset).or_else(|| right.segment_at(offset)),
+        }
+    }
+
+    // All segments in source order, as for renaming a scope
+    pub fn segments(&self) -> Vec<&Segment<'a>> {
+        let mut segments = Vec::new();
+        self.collect_segments(&mut segments);
+        segments
+    }
+
+    fn collect_segments<'n>(&'n self, segments: &mut Vec<&'n Segment<'a>>) {
+        match self {
+            Node::Path { scopes, .. } => segments.extend(scopes.iter().flat_map(|scope| &scope.segments)),
+            Node::Group { selector, .. } => selector.collect_segments(segments),
+            Node::Negate { operand, .. } => operand.collect_segments(segments),
+            Node::Binary { left, right, .. } => {
+                left.collect_segments(segments);
+                right.collect_segments(segments);
+            }
+        }
+    }
+}
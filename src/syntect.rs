@@ -0,0 +1,181 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use ::syntect::highlighting::{ScopeSelector, ScopeSelectors};
+use ::syntect::parsing::{ParseScopeError, Scope, ScopeStack};
+
+use crate::matchers::{
+    alternatives, any_of, conjuncts, CompositeMatcher, GroupMatcher, Matcher, MatcherNode, PathMatcher,
+    ScopeMatcher, SegmentMatcher, TrueMatcher,
+};
+use crate::scope::{ScopeLike, ScopeStackLike};
+
+// Selectors that have no syntect `ScopeSelectors` equivalent
+#[derive(Debug)]
+pub enum ConversionError {
+    // syntect has no `L:`, `R:` and `B:` prefixes
+    Prefix,
+    // syntect scopes only hold literal segments, and a path only a lone `*`
+    Segment,
+    // syntect selectors are one path minus excluded paths
+    Operator,
+    // syntect rejects the scope, for example for having too many segments
+    Scope(ParseScopeError),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::Prefix => f.write_str("syntect selectors have no prefixes"),
+            ConversionError::Segment => f.write_str("syntect scopes only hold literal segments"),
+            ConversionError::Operator => f.write_str("syntect selectors only exclude paths from a path"),
+            ConversionError::Scope(err) => write!(f, "invalid syntect scope: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+// Scope stack as the space-separated string matchers work on
+pub fn scopes_string(stack: &[Scope]) -> String {
+    stack.iter().map(|scope| scope.build_string()).collect::<Vec<_>>().join(" ")
+}
+
+pub fn matches(matcher: &dyn Matcher, stack: &[Scope]) -> bool {
+    matcher.matches_stack(&stack)
+}
+
+pub fn get_prefix(matcher: &dyn Matcher, stack: &[Scope]) -> Option<char> {
+    matcher.get_prefix(&scopes_string(stack))
+}
+
+impl ScopeLike for Scope {
+    fn for_each_segment(&self, visit: &mut dyn FnMut(&str) -> bool) {
+        for segment in self.build_string().split('.') {
+            if !visit(segment) {
+                break;
+            }
+        }
+    }
+}
+
+impl ScopeStackLike for ScopeStack {
+    fn for_each_scope(&self, visit: &mut dyn FnMut(&dyn ScopeLike) -> bool) {
+        self.as_slice().for_each_scope(visit)
+    }
+}
+
+// Matcher equivalent to syntect selectors, or `None` when there are none
+pub fn from_scope_selectors(selectors: &ScopeSelectors) -> Option<Box<dyn Matcher>> {
+    any_of(selectors.selectors.iter().map(from_scope_selector).collect())
+}
+
+pub fn from_scope_selector(selector: &ScopeSelector) -> Box<dyn Matcher> {
+    let path = path_matcher(&selector.path);
+    let excludes: Vec<Box<dyn Matcher>> = selector.excludes.iter().map(path_matcher).collect();
+    match any_of(excludes) {
+        Some(excluded) => {
+            let excluded: Box<dyn Matcher> = match excluded.node() {
+                MatcherNode::Or(..) => Box::new(GroupMatcher::new(None, excluded)),
+                _ => excluded,
+            };
+            Box::new(CompositeMatcher::new(path, '-', excluded))
+        }
+        None => path,
+    }
+}
+
+// An empty syntect stack matches everything, like a lone `*`
+fn path_matcher(stack: &ScopeStack) -> Box<dyn Matcher> {
+    let scopes: Vec<Box<dyn Matcher>> = if stack.is_empty() {
+        vec![Box::new(ScopeMatcher::new(vec![Box::new(TrueMatcher)]))]
+    } else {
+        stack
+            .as_slice()
+            .iter()
+            .map(|scope| {
+                let segments = scope
+                    .build_string()
+                    .split('.')
+                    .map(|segment| Box::new(SegmentMatcher::new(segment)) as Box<dyn Matcher>)
+                    .collect();
+                Box::new(ScopeMatcher::new(segments)) as Box<dyn Matcher>
+            })
+            .collect()
+    };
+    Box::new(PathMatcher::new(None, scopes))
+}
+
+// syntect selectors equivalent to a matcher, for selectors made of paths,
+// `,`, `|` and `-`
+pub fn to_scope_selectors(matcher: &dyn Matcher) -> Result<ScopeSelectors, ConversionError> {
+    let selectors = alternatives(matcher)
+        .into_iter()
+        .map(to_scope_selector)
+        .collect::<Result<_, _>>()?;
+    Ok(ScopeSelectors { selectors })
+}
+
+fn to_scope_selector(alternative: &dyn Matcher) -> Result<ScopeSelector, ConversionError> {
+    let mut selector = ScopeSelector::default();
+    let mut has_path = false;
+
+    for (positive, conjunct) in conjuncts(alternative) {
+        if positive {
+            if has_path {
+                return Err(ConversionError::Operator);
+            }
+            selector.path = scope_stack(conjunct)?;
+            has_path = true;
+        } else {
+            for excluded in alternatives(conjunct) {
+                selector.excludes.push(scope_stack(excluded)?);
+            }
+        }
+    }
+
+    Ok(selector)
+}
+
+fn scope_stack(path: &dyn Matcher) -> Result<ScopeStack, ConversionError> {
+    let scopes = match path.node() {
+        MatcherNode::Path(Some(_), _) => return Err(ConversionError::Prefix),
+        MatcherNode::Path(None, scopes) => scopes,
+        _ => return Err(ConversionError::Operator),
+    };
+
+    if let [scope] = scopes {
+        if is_wildcard(scope.as_ref()) {
+            return Ok(ScopeStack::new());
+        }
+    }
+
+    scopes
+        .iter()
+        .map(|scope| {
+            let segments = match scope.node() {
+                MatcherNode::Scope(segments) => segments
+                    .iter()
+                    .map(|segment| match segment.node() {
+                        MatcherNode::Segment(segment) => Ok(segment),
+                        _ => Err(ConversionError::Segment),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                _ => return Err(ConversionError::Segment),
+            };
+            Scope::new(&segments.join(".")).map_err(ConversionError::Scope)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(ScopeStack::from_vec)
+}
+
+fn is_wildcard(scope: &dyn Matcher) -> bool {
+    match scope.node() {
+        MatcherNode::Scope([segment]) => matches!(segment.node(), MatcherNode::Wildcard),
+        MatcherNode::Wildcard => true,
+        _ => false,
+    }
+}
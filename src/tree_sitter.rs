@@ -0,0 +1,119 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::matchers::Matcher;
+
+// Capture names of common tree-sitter highlight queries mapped to TextMate scopes
+pub const DEFAULT_CAPTURES: &[(&str, &str)] = &[
+    ("attribute", "entity.other.attribute-name"),
+    ("boolean", "constant.language.boolean"),
+    ("character", "constant.character"),
+    ("constant.builtin", "constant.language"),
+    ("constructor", "entity.name.function.constructor"),
+    ("function", "entity.name.function"),
+    ("function.builtin", "support.function"),
+    ("function.macro", "entity.name.function.macro"),
+    ("function.method", "entity.name.function.member"),
+    ("label", "entity.name.label"),
+    ("module", "entity.name.namespace"),
+    ("namespace", "entity.name.namespace"),
+    ("number", "constant.numeric"),
+    ("operator", "keyword.operator"),
+    ("property", "variable.other.property"),
+    ("string.escape", "constant.character.escape"),
+    ("string.special", "string.other"),
+    ("string.special.symbol", "constant.other.symbol"),
+    ("tag", "entity.name.tag"),
+    ("tag.attribute", "entity.other.attribute-name"),
+    ("type", "entity.name.type"),
+    ("type.builtin", "support.type"),
+    ("variable.builtin", "variable.language"),
+];
+
+// CaptureScopeMap, capture names mapped to TextMate scopes by their longest
+// mapped prefix of segments, the remaining segments being kept
+#[derive(Clone, Debug, Default)]
+pub struct CaptureScopeMap {
+    rules: Vec<(String, String)>,
+    language: Option<String>,
+}
+
+impl CaptureScopeMap {
+    // Maps every capture to the scope of the same name
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut map = Self::new();
+        for (capture, scope) in entries {
+            map.insert(capture, scope);
+        }
+        map
+    }
+
+    pub fn defaults() -> Self {
+        Self::from_entries(DEFAULT_CAPTURES.iter().copied())
+    }
+
+    // Language suffix appended to every scope, as in `keyword.control.rust`
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    // Adds a mapping, replacing the scope of the same capture
+    pub fn insert(&mut self, capture: &str, scope: &str) {
+        let capture = capture.trim_start_matches('@');
+        match self.rules.iter_mut().find(|(existing, _)| existing == capture) {
+            Some((_, existing)) => *existing = scope.to_string(),
+            None => self.rules.push((capture.to_string(), scope.to_string())),
+        }
+    }
+
+    // TextMate scope of a capture name, with or without its leading `@`
+    pub fn scope(&self, capture: &str) -> String {
+        let capture = capture.trim_start_matches('@');
+        let mapped = self
+            .rules
+            .iter()
+            .filter(|(prefix, _)| {
+                capture
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len());
+
+        let mut scope = match mapped {
+            Some((prefix, scope)) => [scope.as_str(), &capture[prefix.len()..]].concat(),
+            None => capture.to_string(),
+        };
+        if let Some(language) = &self.language {
+            scope.push('.');
+            scope.push_str(language);
+        }
+        scope
+    }
+
+    // Scope stack of nested captures, outermost first, on top of the root
+    // scope of the document such as `source.rust`
+    pub fn scope_stack<'a>(&self, root: &str, captures: impl IntoIterator<Item = &'a str>) -> String {
+        let mut stack = String::from(root);
+        for capture in captures {
+            if !stack.is_empty() {
+                stack.push(' ');
+            }
+            stack.push_str(&self.scope(capture));
+        }
+        stack
+    }
+
+    pub fn matches<'a>(
+        &self,
+        matcher: &dyn Matcher,
+        root: &str,
+        captures: impl IntoIterator<Item = &'a str>,
+    ) -> bool {
+        matcher.matches(&self.scope_stack(root, captures))
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::lint::Severity;
+use crate::ParseOptions;
+
+// Root scopes from the naming conventions of the TextMate manual, plus the
+// `source` and `text` roots of grammars
+pub const STANDARD_ROOTS: &[&str] = &[
+    "comment", "constant", "entity", "invalid", "keyword", "markup", "meta", "storage", "string", "support",
+    "variable", "source", "text",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScopeIssue {
+    // A character the selector grammar does not accept in a segment
+    InvalidCharacter(char),
+    // Nothing between two dots, or before or after one
+    EmptySegment,
+    // A segment starting with `-`, which a selector reads as an operator
+    LeadingHyphen,
+    // A first segment outside of `STANDARD_ROOTS`
+    NonStandardRoot,
+    // The last segment names none of the languages of the scope stack
+    MissingLanguageSuffix,
+    // No `source` or `text` scope names a language, and none was given, so
+    // language suffixes are not checked
+    UnknownLanguage,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopeDiagnostic {
+    // Byte offset into the validated scopes
+    pub offset: usize,
+    pub issue: ScopeIssue,
+}
+
+impl ScopeDiagnostic {
+    pub fn severity(&self) -> Severity {
+        match self.issue {
+            ScopeIssue::NonStandardRoot | ScopeIssue::MissingLanguageSuffix | ScopeIssue::UnknownLanguage => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for ScopeDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "offset {}: ", self.offset)?;
+        match &self.issue {
+            ScopeIssue::InvalidCharacter(c) => write!(f, "character {:?} is not allowed in a segment", c),
+            ScopeIssue::EmptySegment => f.write_str("empty segment"),
+            ScopeIssue::LeadingHyphen => f.write_str("segment starts with `-`"),
+            ScopeIssue::NonStandardRoot => f.write_str("non-standard root scope"),
+            ScopeIssue::MissingLanguageSuffix => f.write_str("missing language suffix"),
+            ScopeIssue::UnknownLanguage => f.write_str("no language to check suffixes against"),
+        }
+    }
+}
+
+// Checks a scope name, or a space-separated scope stack, against the segment
+// grammar of `parse` and the naming conventions
+pub fn validate(scopes: &str) -> Vec<ScopeDiagnostic> {
+    validate_with_options(scopes, &ParseOptions::DEFAULT)
+}
+
+// Checks scopes against the segment grammar of `parse_with_options`
+pub fn validate_with_options(scopes: &str, options: &ParseOptions) -> Vec<ScopeDiagnostic> {
+    validate_scopes(scopes, options, None)
+}
+
+// Checks scopes of a grammar for `language`, such as `js`, which need not be
+// in the scope stack
+pub fn validate_with_language(scopes: &str, language: &str) -> Vec<ScopeDiagnostic> {
+    validate_scopes(scopes, &ParseOptions::DEFAULT, Some(language))
+}
+
+fn validate_scopes(scopes: &str, options: &ParseOptions, language: Option<&str>) -> Vec<ScopeDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let scopes: Vec<(usize, &str)> = scopes
+        .split(' ')
+        .scan(0, |offset, scope| {
+            let start = *offset;
+            *offset += scope.len() + 1;
+            Some((start, scope))
+        })
+        .filter(|(_, scope)| !scope.is_empty())
+        .collect();
+
+    // Languages are the segments after the root of the `source` and `text` scopes
+    let languages: Vec<&str> = scopes
+        .iter()
+        .filter(|(_, scope)| scope.starts_with("source.") || scope.starts_with("text."))
+        .flat_map(|(_, scope)| scope.split('.').skip(1))
+        .chain(language)
+        .collect();
+    let mut unknown_language = None;
+
+    for &(start, scope) in &scopes {
+        validate_segments(start, scope, options, &mut diagnostics);
+
+        let root = scope.split('.').next().unwrap_or_default();
+        if !STANDARD_ROOTS.contains(&root) {
+            diagnostics.push(ScopeDiagnostic { offset: start, issue: ScopeIssue::NonStandardRoot });
+        }
+
+        let (rest, language) = scope.rsplit_once('.').unwrap_or(("", scope));
+        let missing_language = if rest.is_empty() {
+            true
+        } else if root == "source" || root == "text" {
+            false
+        } else if languages.is_empty() {
+            unknown_language.get_or_insert(start);
+            false
+        } else {
+            !languages.contains(&language)
+        };
+        if missing_language {
+            diagnostics.push(ScopeDiagnostic {
+                offset: start + scope.len(),
+                issue: ScopeIssue::MissingLanguageSuffix,
+            });
+        }
+    }
+
+    // Once, for the first scope whose suffix went unchecked
+    if let Some(offset) = unknown_language {
+        diagnostics.push(ScopeDiagnostic { offset, issue: ScopeIssue::UnknownLanguage });
+    }
+
+    diagnostics
+}
+
+fn validate_segments(start: usize, scope: &str, options: &ParseOptions, diagnostics: &mut Vec<ScopeDiagnostic>) {
+    let mut offset = start;
+    for segment in scope.split('.') {
+        if segment.is_empty() {
+            diagnostics.push(ScopeDiagnostic { offset, issue: ScopeIssue::EmptySegment });
+        } else if segment.starts_with('-') {
+            diagnostics.push(ScopeDiagnostic { offset, issue: ScopeIssue::LeadingHyphen });
+        }
+
+        for (index, c) in segment.char_indices() {
+            if !is_segment_char(c, options) {
+                diagnostics.push(ScopeDiagnostic {
+                    offset: offset + index,
+                    issue: ScopeIssue::InvalidCharacter(c),
+                });
+            }
+        }
+        offset += segment.len() + 1;
+    }
+}
+
+// Same characters as the `segment_char` rule of the grammar
+fn is_segment_char(c: char, options: &ParseOptions) -> bool {
+    matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '+')
+        || (options.unicode_segments && !c.is_ascii() && unicode_ident::is_xid_continue(c))
+}
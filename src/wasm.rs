@@ -0,0 +1,53 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+
+use crate::matchers::Matcher;
+
+// ScopeSelector, mirroring the JavaScript API of first-mate
+#[wasm_bindgen]
+pub struct ScopeSelector {
+    matcher: Box<dyn Matcher>,
+}
+
+#[wasm_bindgen]
+impl ScopeSelector {
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: &str) -> Result<ScopeSelector, JsError> {
+        match crate::parse(source) {
+            Ok(matcher) => Ok(Self { matcher }),
+            Err(err) => Err(JsError::new(&err.to_string())),
+        }
+    }
+
+    pub fn matches(&self, scopes: JsValue) -> bool {
+        self.matcher.matches(&join_scopes(scopes))
+    }
+
+    #[wasm_bindgen(js_name = getPrefix)]
+    pub fn get_prefix(&self, scopes: JsValue) -> Option<String> {
+        self.matcher.get_prefix(&join_scopes(scopes)).map(String::from)
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.matcher.to_string()
+    }
+}
+
+// Accepts a single scope string or an array of scopes, as first-mate does
+fn join_scopes(scopes: JsValue) -> String {
+    if let Some(scope) = scopes.as_string() {
+        return scope;
+    }
+
+    Array::from(&scopes)
+        .iter()
+        .filter_map(|scope| scope.as_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,153 @@
+use textmate_scope_selector_peg::automaton::SelectorAutomaton;
+
+#[derive(Clone, Debug)]
+struct AutomatonTestCase {
+    input: String,
+    expected: Vec<usize>,
+}
+
+fn build(selectors: &[&str]) -> SelectorAutomaton {
+    selectors
+        .iter()
+        .map(|selector| match textmate_scope_selector_peg::parse(selector) {
+            Ok(selector) => selector,
+            Err(err) => panic!("Parsing error for selector {}: {}", selector, err),
+        })
+        .collect()
+}
+
+#[test]
+fn test_automaton_paths() {
+    let automaton = build(&[
+        "comment",
+        "string.quoted",
+        "source.js string",
+        "source.* string.quoted.double",
+        "a b c",
+        "*",
+    ]);
+    let test_cases = vec![
+        AutomatonTestCase {
+            input: "source.js string.quoted.double.js".to_string(),
+            expected: vec![1, 2, 3, 5],
+        },
+        AutomatonTestCase {
+            input: "source.ts comment.line".to_string(),
+            expected: vec![0, 5],
+        },
+        AutomatonTestCase {
+            input: "a x b y c".to_string(),
+            expected: vec![4, 5],
+        },
+        AutomatonTestCase {
+            input: "c b a".to_string(),
+            expected: vec![5],
+        },
+    ];
+
+    for case in test_cases {
+        let result = automaton.matches(&case.input);
+        assert_eq!(
+            result, case.expected,
+            "Test failed for input: \"{}\"",
+            case.input
+        );
+    }
+}
+
+#[test]
+fn test_automaton_post_filters() {
+    let automaton = build(&[
+        "source.js -comment",
+        "string & -string.regexp",
+        "(a, b) & c",
+        "-comment",
+        "a | b c",
+        "L:(x, y)",
+    ]);
+    let test_cases = vec![
+        AutomatonTestCase {
+            input: "source.js".to_string(),
+            expected: vec![0, 3],
+        },
+        AutomatonTestCase {
+            input: "source.js comment".to_string(),
+            expected: vec![],
+        },
+        AutomatonTestCase {
+            input: "string.regexp".to_string(),
+            expected: vec![3],
+        },
+        AutomatonTestCase {
+            input: "string.quoted".to_string(),
+            expected: vec![1, 3],
+        },
+        AutomatonTestCase {
+            input: "b c".to_string(),
+            expected: vec![2, 3, 4],
+        },
+        AutomatonTestCase {
+            input: "y".to_string(),
+            expected: vec![3, 5],
+        },
+    ];
+
+    for case in test_cases {
+        let result = automaton.matches(&case.input);
+        assert_eq!(
+            result, case.expected,
+            "Test failed for input: \"{}\"",
+            case.input
+        );
+    }
+}
+
+#[test]
+fn test_automaton_agrees_with_tree() {
+    let selectors = [
+        "a", "a.b", "a.*.c", "a b", "a b e", "a - c", "-c", "a-b", "a & -b",
+        "a&b&c", "a, b, c", "a,", "(a,b) | (c, d)", "R:g", "*", "a | b",
+    ];
+    let inputs = [
+        "a", "b", "a.b", "a.b.c", "a.b.c.d", "a.b.d.c", "a b", "b a",
+        "a b c d e", "a c", "a-b", "c", "d", "g", "b c", "",
+    ];
+    let automaton = build(&selectors);
+
+    for input in inputs {
+        let expected: Vec<usize> = selectors
+            .iter()
+            .enumerate()
+            .filter(|(_, selector)| textmate_scope_selector_peg::parse(selector).unwrap().matches(input))
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(
+            automaton.matches(input), expected,
+            "Test failed for input: \"{}\"",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_automaton_cache_limit() {
+    let selectors = ["a", "a b", "a b c", "b c", "c d", "a - d"];
+    let inputs = ["a b c", "a c d", "b c d", "a d", "c a b", "d c b a", "a b c d", "b"];
+    let parsed = || selectors.iter().map(|selector| textmate_scope_selector_peg::parse(selector).unwrap()).collect();
+    let unbounded = SelectorAutomaton::new(parsed());
+    let bounded = SelectorAutomaton::with_cache_limit(parsed(), 3);
+
+    // Repeated passes hit the cache, then run again after flushes
+    for _ in 0..3 {
+        for input in inputs {
+            assert_eq!(
+                bounded.matches(input),
+                unbounded.matches(input),
+                "Test failed for input: \"{}\"",
+                input
+            );
+            assert!(bounded.cached_states() <= 3, "Test failed for input: \"{}\"", input);
+        }
+    }
+    assert!(unbounded.cached_states() > 3);
+}
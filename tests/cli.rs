@@ -0,0 +1,89 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Clone, Debug)]
+struct CliTestCase {
+    args: Vec<String>,
+    stdin: String,
+    expected_stdout: String,
+    expected_code: i32,
+}
+
+fn run(case: &CliTestCase) -> (String, String, i32) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tmsel"))
+        .args(&case.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run tmsel");
+    child.stdin.take().unwrap().write_all(case.stdin.as_bytes()).unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+        output.status.code().unwrap(),
+    )
+}
+
+#[test]
+fn test_cli_results() {
+    let test_cases = vec![
+        CliTestCase {
+            args: vec!["source.js -comment".to_string(), "source.js string".to_string(), "source.js comment".to_string()],
+            stdin: String::new(),
+            expected_stdout: concat!(
+                "{\"scopes\":\"source.js string\",\"matches\":true,\"prefix\":null}\n",
+                "{\"scopes\":\"source.js comment\",\"matches\":false,\"prefix\":null}\n",
+            ).to_string(),
+            expected_code: 0,
+        },
+        CliTestCase {
+            args: vec!["L:text.html.markdown".to_string()],
+            stdin: "text.html.markdown meta.paragraph.markdown\nsource.\"js\"\n".to_string(),
+            expected_stdout: concat!(
+                "{\"scopes\":\"text.html.markdown meta.paragraph.markdown\",\"matches\":true,\"prefix\":\"L\"}\n",
+                "{\"scopes\":\"source.\\\"js\\\"\",\"matches\":false,\"prefix\":null}\n",
+            ).to_string(),
+            expected_code: 0,
+        },
+        CliTestCase {
+            args: vec!["--".to_string(), "-comment".to_string(), "string".to_string()],
+            stdin: String::new(),
+            expected_stdout: "{\"scopes\":\"string\",\"matches\":true,\"prefix\":null}\n".to_string(),
+            expected_code: 0,
+        },
+        CliTestCase {
+            args: vec![],
+            stdin: String::new(),
+            expected_stdout: String::new(),
+            expected_code: 2,
+        },
+    ];
+
+    for case in test_cases {
+        let (stdout, stderr, code) = run(&case);
+        assert_eq!(
+            (stdout.as_str(), code), (case.expected_stdout.as_str(), case.expected_code),
+            "Test failed for args: {:?}, stderr: {}",
+            case.args, stderr
+        );
+    }
+}
+
+#[test]
+fn test_cli_syntax_error() {
+    let case = CliTestCase {
+        args: vec!["a, (b".to_string(), "a".to_string()],
+        stdin: String::new(),
+        expected_stdout: String::new(),
+        expected_code: 1,
+    };
+
+    let (stdout, stderr, code) = run(&case);
+    assert_eq!((stdout.as_str(), code), ("", 1));
+    assert!(stderr.ends_with("at 1:6\n  a, (b\n       ^\n"), "Unexpected stderr: {}", stderr);
+}
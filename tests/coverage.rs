@@ -0,0 +1,103 @@
+use textmate_scope_selector_peg::coverage::coverage;
+
+const CORPUS: &[&str] = &[
+    "source.js comment.line.double-slash.js",
+    "source.js string.quoted.double.js",
+    "source.js string.quoted.single.js",
+    "source.js string.template.js",
+    "source.js keyword.control.js",
+];
+
+#[derive(Clone, Debug)]
+struct CoverageTestCase {
+    selectors: Vec<&'static str>,
+    // Matched and won counts of each rule
+    expected: Vec<(usize, usize)>,
+    unused: Vec<usize>,
+}
+
+#[test]
+fn test_coverage() {
+    let test_cases = vec![
+        CoverageTestCase {
+            selectors: vec!["comment", "string", "string.quoted", "markup.heading"],
+            expected: vec![(1, 1), (3, 1), (2, 2), (0, 0)],
+            unused: vec![3],
+        },
+        CoverageTestCase {
+            selectors: vec!["string", "source string", "*"],
+            expected: vec![(3, 0), (3, 3), (5, 2)],
+            unused: vec![],
+        },
+        CoverageTestCase {
+            selectors: vec!["keyword", "keyword", "-source"],
+            expected: vec![(1, 0), (1, 1), (0, 0)],
+            unused: vec![2],
+        },
+    ];
+
+    for case in test_cases {
+        let selectors: Vec<_> = case
+            .selectors
+            .iter()
+            .map(|selector| textmate_scope_selector_peg::parse(selector).unwrap())
+            .collect();
+        let report = coverage(&selectors, CORPUS.iter().copied());
+
+        let result: Vec<(usize, usize)> = report.rules.iter().map(|rule| (rule.matched, rule.won)).collect();
+        let unused: Vec<usize> = report.unused().map(|rule| rule.index).collect();
+        assert_eq!(report.stacks, CORPUS.len());
+        assert_eq!(result, case.expected, "Test failed for selectors: {:?}", case.selectors);
+        assert_eq!(unused, case.unused, "Test failed for selectors: {:?}", case.selectors);
+    }
+}
+
+#[test]
+fn test_coverage_nested_stacks() {
+    let selectors: Vec<_> = ["comment", "string", "source.js string"]
+        .iter()
+        .map(|selector| textmate_scope_selector_peg::parse(selector).unwrap())
+        .collect();
+    let corpus = [
+        "source.js string.quoted comment.block",
+        "source.js string.template comment.line",
+        "source.js comment.block string.quoted",
+    ];
+    let report = coverage(&selectors, corpus);
+
+    let result: Vec<(usize, usize)> = report.rules.iter().map(|rule| (rule.matched, rule.won)).collect();
+    assert_eq!(result, vec![(3, 2), (3, 0), (3, 1)]);
+    assert_eq!(report.overridden().map(|rule| rule.index).collect::<Vec<_>>(), vec![1]);
+}
+
+#[test]
+fn test_coverage_output() {
+    let selectors: Vec<_> = ["comment,string", "string.quoted -string.quoted.single", "string - string"]
+        .iter()
+        .map(|selector| textmate_scope_selector_peg::parse(selector).unwrap())
+        .collect();
+    let report = coverage(&selectors, CORPUS.iter().copied());
+
+    assert_eq!(
+        report.to_json(),
+        concat!(
+            r#"{"stacks":5,"rules":["#,
+            r#"{"index":0,"selector":"comment, string","matched":4,"won":3},"#,
+            r#"{"index":1,"selector":"string.quoted - string.quoted.single","matched":1,"won":1},"#,
+            r#"{"index":2,"selector":"string - string","matched":0,"won":0}"#,
+            r#"],"unused":[2]}"#
+        )
+    );
+    assert_eq!(
+        report.to_string(),
+        [
+            " rule  matched      won  selector",
+            "    0        4        3  comment, string",
+            "    1        1        1  string.quoted - string.quoted.single",
+            "    2        0        0  string - string  (unused)",
+            "5 scope stacks, 1 unused rules",
+        ]
+        .join("\n")
+    );
+    assert_eq!(report.overridden().count(), 0);
+}
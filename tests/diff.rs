@@ -0,0 +1,120 @@
+use textmate_scope_selector_peg::diff::{diff, Side};
+
+#[derive(Clone, Debug)]
+struct DiffTestCase {
+    before: Vec<(&'static str, &'static str)>,
+    after: Vec<(&'static str, &'static str)>,
+    // Changed stacks with the winning selector on each side
+    expected: Vec<(&'static str, Option<&'static str>, Option<&'static str>)>,
+}
+
+const CORPUS: &[&str] = &[
+    "source.js comment.line.double-slash.js",
+    "source.js string.quoted.double.js",
+    "source.js string.template.js",
+    "source.js keyword.control.js",
+    "source.js meta.block.js",
+];
+
+#[test]
+fn test_diff() {
+    let test_cases = vec![
+        DiffTestCase {
+            before: vec![("comment", "#888"), ("string", "#0a0")],
+            after: vec![("comment", "#888"), ("string", "#0a0")],
+            expected: vec![],
+        },
+        DiffTestCase {
+            before: vec![("comment", "#888"), ("string", "#0a0")],
+            after: vec![("comment", "#777"), ("string", "#0a0")],
+            expected: vec![("source.js comment.line.double-slash.js", Some("comment"), Some("comment"))],
+        },
+        DiffTestCase {
+            before: vec![("string", "#0a0")],
+            after: vec![("string", "#0a0"), ("string.template", "#0a0")],
+            expected: vec![("source.js string.template.js", Some("string"), Some("string.template"))],
+        },
+        DiffTestCase {
+            before: vec![("keyword", "#00f"), ("string , comment", "#0a0")],
+            after: vec![("string,comment", "#0a0"), ("keyword.control", "#00f"), ("keyword", "#f00")],
+            expected: vec![("source.js keyword.control.js", Some("keyword"), Some("keyword.control"))],
+        },
+        DiffTestCase {
+            before: vec![("meta", "#111"), ("source", "#222")],
+            after: vec![("source", "#222"), ("meta", "#111")],
+            expected: vec![],
+        },
+        DiffTestCase {
+            before: vec![("source.js meta", "#111"), ("meta", "#222")],
+            after: vec![("meta", "#222"), ("source.js meta", "#111")],
+            expected: vec![],
+        },
+        DiffTestCase {
+            before: vec![("keyword", "#00f")],
+            after: vec![("keyword - keyword.control", "#00f")],
+            expected: vec![("source.js keyword.control.js", Some("keyword"), None)],
+        },
+    ];
+
+    for case in test_cases {
+        let result: Vec<(&str, Option<&str>, Option<&str>)> = diff(&case.before, &case.after, CORPUS)
+            .unwrap()
+            .into_iter()
+            .map(|change| {
+                (
+                    change.scopes,
+                    change.before.map(|winner| winner.selector),
+                    change.after.map(|winner| winner.selector),
+                )
+            })
+            .collect();
+        assert_eq!(
+            result, case.expected,
+            "Test failed for rules: {:?} and {:?}",
+            case.before, case.after
+        );
+    }
+}
+
+#[test]
+fn test_diff_ties() {
+    // Rules of different specificity win regardless of their order
+    let before = [("string", 1), ("source string", 2), ("*", 3)];
+    let after = [("*", 3), ("source string", 2), ("string", 1)];
+    let changes = diff(&before, &after, &["source.js string.quoted", "string.quoted", "comment"]).unwrap();
+
+    assert!(changes.is_empty());
+
+    // Equally specific rules resolve to the later one, so reordering them
+    // changes the payload even though the selector is the same
+    let before = [("string.quoted", 1), ("string.quoted", 2)];
+    let after = [("string.quoted", 2), ("string.quoted", 1)];
+    let changes = diff(&before, &after, &["source.js string.quoted"]).unwrap();
+
+    assert_eq!(changes.len(), 1);
+    let change = &changes[0];
+    assert_eq!(change.before.as_ref().map(|winner| (winner.index, *winner.payload)), Some((1, 2)));
+    assert_eq!(change.after.as_ref().map(|winner| (winner.index, *winner.payload)), Some((1, 1)));
+}
+
+#[test]
+fn test_diff_nested_stacks() {
+    // The rule for the innermost scope wins wherever it is listed
+    let stacks = ["source.js string.quoted comment.block", "source.js comment.block string.quoted", "source.js"];
+    let changes = diff(&[("comment", 1), ("string", 2)], &[("string", 2), ("comment", 1)], &stacks).unwrap();
+
+    assert!(changes.is_empty());
+
+    let changes = diff(&[("comment", 1)], &[("comment", 1), ("string", 2)], &stacks).unwrap();
+    let changed: Vec<_> = changes.iter().map(|change| change.scopes).collect();
+    assert_eq!(changed, vec!["source.js comment.block string.quoted"]);
+}
+
+#[test]
+fn test_diff_errors() {
+    let err = diff(&[("string", ())], &[("string", ()), ("(comment", ())], CORPUS).unwrap_err();
+
+    assert_eq!(err.side, Side::After);
+    assert_eq!(err.index, 1);
+    assert!(err.to_string().starts_with("after rule 1: error at 1:9"), "{}", err);
+}
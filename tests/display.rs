@@ -0,0 +1,77 @@
+#[derive(Clone, Debug)]
+struct DisplayTestCase {
+    selector: String,
+    expected: String,
+}
+
+#[test]
+fn test_display_canonical() {
+    let test_cases = vec![
+        DisplayTestCase {
+            selector: "a.b  c".to_string(),
+            expected: "a.b c".to_string(),
+        },
+        DisplayTestCase {
+            selector: "a,b,c".to_string(),
+            expected: "a, b, c".to_string(),
+        },
+        DisplayTestCase {
+            selector: "a,".to_string(),
+            expected: "a".to_string(),
+        },
+        DisplayTestCase {
+            selector: "a&-b".to_string(),
+            expected: "a - b".to_string(),
+        },
+        DisplayTestCase {
+            selector: "a&b|c".to_string(),
+            expected: "a & b | c".to_string(),
+        },
+        DisplayTestCase {
+            selector: "-a.*".to_string(),
+            expected: "-a.*".to_string(),
+        },
+        DisplayTestCase {
+            selector: "L:(a,b) - R:c".to_string(),
+            expected: "L:(a, b) - R:c".to_string(),
+        },
+        DisplayTestCase {
+            selector: "-(a | b)".to_string(),
+            expected: "-(a, b)".to_string(),
+        },
+    ];
+
+    for case in test_cases {
+        match textmate_scope_selector_peg::parse(&case.selector) {
+            Ok(selector) => {
+                let result = selector.to_string();
+                assert_eq!(
+                    result, case.expected,
+                    "Test failed for selector: \"{}\"",
+                    case.selector
+                );
+            }
+            Err(err) => panic!("Parsing error for selector {}: {}", &case.selector, err),
+        }
+    }
+}
+
+#[test]
+fn test_display_round_trip() {
+    let selectors = ["a - b - c", "a - b | c", "(a - b) - c", "a & (b, c)", "-a & b", "B:x y.* - (z)"];
+    let inputs = ["a", "a b", "a c", "a b c", "b c", "x y.z", "x y.z z", "c"];
+
+    for selector in selectors {
+        let parsed = textmate_scope_selector_peg::parse(selector).unwrap();
+        let printed = parsed.to_string();
+        let reparsed = textmate_scope_selector_peg::parse(&printed).unwrap();
+        assert_eq!(printed, reparsed.to_string(), "Test failed for selector: \"{}\"", selector);
+        for input in inputs {
+            assert_eq!(
+                parsed.matches(input), reparsed.matches(input),
+                "Test failed for selector: \"{}\", printed: \"{}\", input: \"{}\"",
+                selector, printed, input
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use textmate_scope_selector_peg::explain::Step;
+
+#[derive(Clone, Debug)]
+struct ExplainTestCase {
+    selector: String,
+    input: String,
+}
+
+#[test]
+fn test_explain_agrees_with_matches() {
+    let cases = [
+        ("source.js -comment string", "source.js comment.line string.quoted"),
+        ("source.js -comment string", "source.js string.quoted"),
+        ("a b c", "a x b"),
+        ("a b c", "a b x c"),
+        ("(a, b) & c", "b c"),
+        ("a | b - c", "c b"),
+        ("L:source.js - (comment, string)", "source.js string.quoted"),
+        ("-a", ""),
+        ("*", ""),
+        ("text - -text.html", "text.html"),
+    ];
+    let test_cases: Vec<ExplainTestCase> = cases
+        .iter()
+        .map(|&(selector, input)| ExplainTestCase {
+            selector: selector.to_string(),
+            input: input.to_string(),
+        })
+        .collect();
+
+    for case in test_cases {
+        let selector = textmate_scope_selector_peg::parse(&case.selector).unwrap();
+        let explanation = selector.explain(&case.input);
+        assert_eq!(
+            explanation.trace.matched,
+            selector.matches(&case.input),
+            "Test failed for selector: \"{}\", input: \"{}\"",
+            case.selector,
+            case.input
+        );
+    }
+}
+
+#[test]
+fn test_explain_steps() {
+    let selector = textmate_scope_selector_peg::parse("source.js -comment string").unwrap();
+    let explanation = selector.explain("source.js comment.line string.quoted");
+
+    let Step::And(left, right) = &explanation.trace.step else {
+        panic!("Expected an and step: {:?}", explanation.trace);
+    };
+    assert!(left.matched);
+    assert!(!right.matched);
+    match &right.step {
+        Step::Negate { inner, at } => {
+            assert_eq!(*at, Some(2));
+            assert_eq!(inner.step, Step::Path {
+                components: vec!["comment".to_string(), "string".to_string()],
+                found: vec![1, 2],
+            });
+        }
+        step => panic!("Expected a negate step: {:?}", step),
+    }
+
+    let selector = textmate_scope_selector_peg::parse("a b c").unwrap();
+    let explanation = selector.explain("a x b");
+    assert_eq!(explanation.trace.step, Step::Path {
+        components: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        found: vec![0, 2],
+    });
+}
+
+#[test]
+fn test_explain_display() {
+    let selector = textmate_scope_selector_peg::parse("source.js -comment string").unwrap();
+
+    assert_eq!(
+        selector.explain("source.js comment.line string.quoted").to_string(),
+        [
+            "failed \"source.js - comment string\": right side failed",
+            "  matched \"source.js\": \"source.js\" at 0 (source.js)",
+            "  failed \"-comment string\": negated selector matched at 2 (string.quoted)",
+            "    matched \"comment string\": \"comment\" at 1 (comment.line), \"string\" at 2 (string.quoted)",
+        ]
+        .join("\n")
+    );
+    assert_eq!(
+        selector.explain("source.ts string.quoted").to_string(),
+        [
+            "failed \"source.js - comment string\": left side failed",
+            "  failed \"source.js\": \"source.js\" not found in the stack",
+            "  matched \"-comment string\"",
+            "    failed \"comment string\": \"comment\" not found in the stack",
+        ]
+        .join("\n")
+    );
+
+    let selector = textmate_scope_selector_peg::parse("a b c, d").unwrap();
+    assert_eq!(
+        selector.explain("a x b").to_string(),
+        [
+            "failed \"a b c, d\": no alternative matched",
+            "  failed \"a b c\": \"c\" not found after 2 (b)",
+            "  failed \"d\": \"d\" not found in the stack",
+        ]
+        .join("\n")
+    );
+}
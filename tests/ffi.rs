@@ -0,0 +1,67 @@
+#![cfg(feature = "ffi")]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Builds the cdylib with the `ffi` feature into its own target directory, so
+// the test never links a library built without the `tmsel_*` functions
+fn build_library() -> PathBuf {
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    let output = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["build", "--lib", "--features", "ffi", "--package", env!("CARGO_PKG_NAME")])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "ffi build failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    target_dir.join("debug")
+}
+
+#[test]
+fn test_header_up_to_date() {
+    let generated = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/tmsel.h")).unwrap();
+    let published = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/include/tmsel.h")).unwrap();
+    assert!(
+        generated == published,
+        "include/tmsel.h is out of date, copy it from {}/tmsel.h",
+        env!("OUT_DIR")
+    );
+}
+
+#[test]
+fn test_c_program() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = build_library();
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("tmsel_ffi_test");
+
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_dir.join("tests/ffi/main.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .arg("-ltextmate_scope_selector_peg")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "failed to compile tests/ffi/main.c");
+
+    // `cargo test` puts its own builds of the cdylib on the library path
+    let output = Command::new(&program)
+        .env("LD_LIBRARY_PATH", &library_dir)
+        .env("DYLD_LIBRARY_PATH", &library_dir)
+        .output()
+        .expect("failed to run the C program");
+    assert!(
+        output.status.success(),
+        "C program failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
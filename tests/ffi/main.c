@@ -0,0 +1,39 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "tmsel.h"
+
+#define CHECK(condition)                                              \
+    do {                                                              \
+        if (!(condition)) {                                           \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,   \
+                    __LINE__, #condition);                            \
+            return 1;                                                 \
+        }                                                             \
+    } while (0)
+
+int main(void) {
+    TmselSelector *selector = tmsel_parse("source.js -comment");
+    CHECK(selector != NULL);
+    CHECK(tmsel_last_error() == NULL);
+    CHECK(tmsel_matches(selector, "source.js string.quoted"));
+    CHECK(!tmsel_matches(selector, "source.js comment.line"));
+    CHECK(tmsel_get_prefix(selector, "source.js") == '\0');
+    tmsel_free(selector);
+
+    selector = tmsel_parse("L:text.html.markdown - (comment, string)");
+    CHECK(selector != NULL);
+    CHECK(tmsel_get_prefix(selector, "text.html.markdown meta.paragraph.markdown") == 'L');
+    CHECK(tmsel_get_prefix(selector, "text.html.markdown comment") == '\0');
+    CHECK(!tmsel_matches(selector, NULL));
+    CHECK(strcmp(tmsel_last_error(), "unexpected null pointer") == 0);
+    tmsel_free(selector);
+
+    selector = tmsel_parse("a, (b");
+    CHECK(selector == NULL);
+    CHECK(tmsel_last_error() != NULL);
+    CHECK(strstr(tmsel_last_error(), "1:6") != NULL);
+
+    tmsel_free(NULL);
+    return 0;
+}
@@ -0,0 +1,137 @@
+use textmate_scope_selector_peg::matchers::MatcherNode;
+use textmate_scope_selector_peg::ParseOptions;
+
+#[derive(Clone, Debug)]
+struct GlobTestCase {
+    selector: String,
+    input: String,
+    expected: bool,
+}
+
+fn glob_options() -> ParseOptions {
+    ParseOptions {
+        glob_segments: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn test_glob_segments() {
+    let test_cases = vec![
+        GlobTestCase {
+            selector: "punctuation.definition.*-begin".to_string(),
+            input: "punctuation.definition.string-begin.js".to_string(),
+            expected: true,
+        },
+        GlobTestCase {
+            selector: "punctuation.definition.*-begin".to_string(),
+            input: "punctuation.definition.string-end.js".to_string(),
+            expected: false,
+        },
+        GlobTestCase {
+            selector: "meta.*tag*".to_string(),
+            input: "text.html meta.selftag.html".to_string(),
+            expected: true,
+        },
+        GlobTestCase {
+            selector: "meta.*tag*".to_string(),
+            input: "text.html meta.tag".to_string(),
+            expected: true,
+        },
+        GlobTestCase {
+            selector: "meta.*tag*".to_string(),
+            input: "text.html meta.ta.g".to_string(),
+            expected: false,
+        },
+        GlobTestCase {
+            selector: "string.quoted.*-escape".to_string(),
+            input: "string.quoted.double-escape".to_string(),
+            expected: true,
+        },
+        GlobTestCase {
+            selector: "markup.heading.* - markup.h?".to_string(),
+            input: "markup.heading.1 markup.h1".to_string(),
+            expected: false,
+        },
+        GlobTestCase {
+            selector: "markup.heading.* - markup.h?".to_string(),
+            input: "markup.heading.1 markup.h10".to_string(),
+            expected: true,
+        },
+        GlobTestCase {
+            selector: "a*b*c".to_string(),
+            input: "abxbxc".to_string(),
+            expected: true,
+        },
+        GlobTestCase {
+            selector: "a*b*c".to_string(),
+            input: "abxbxcx".to_string(),
+            expected: false,
+        },
+    ];
+
+    for case in test_cases {
+        match textmate_scope_selector_peg::parse_with_options(&case.selector, &glob_options()) {
+            Ok(selector) => {
+                let result = selector.matches(&case.input);
+                assert_eq!(
+                    result, case.expected,
+                    "Test failed for selector: \"{}\", input: \"{}\"",
+                    case.selector, case.input
+                );
+            }
+            Err(err) => panic!("Parsing error for selector {}: {}", &case.selector, err),
+        }
+    }
+}
+
+#[test]
+fn test_glob_segments_ignoring_case() {
+    let options = ParseOptions {
+        case_insensitive: true,
+        ..glob_options()
+    };
+    let selector = textmate_scope_selector_peg::parse_with_options("Meta.*Tag*", &options).unwrap();
+
+    assert!(selector.matches("meta.selftag.html"));
+    assert!(!selector.matches("meta.self"));
+}
+
+#[test]
+fn test_glob_segments_disabled() {
+    for (selector, offset) in [("meta.*tag*", 10), ("source.js meta.t?g", 18), ("a.b*, c", 4)] {
+        match textmate_scope_selector_peg::parse(selector) {
+            Ok(_) => panic!("Expected a parsing error for selector: \"{}\"", selector),
+            Err(err) => {
+                assert_eq!(err.location.offset, offset, "Test failed for selector: \"{}\"", selector);
+                assert!(
+                    err.to_string().contains("ParseOptions::glob_segments"),
+                    "Test failed for selector: \"{}\", error: {}",
+                    selector,
+                    err
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_lone_wildcard() {
+    // Without the extension `*-b` stays a subtraction, with it a glob
+    let selector = textmate_scope_selector_peg::parse("a.*-b").unwrap();
+    assert_eq!(selector.to_string(), "a.* - b");
+
+    let selector = textmate_scope_selector_peg::parse_with_options("a.*-b", &glob_options()).unwrap();
+    assert_eq!(selector.to_string(), "a.*-b");
+    assert!(selector.matches("a.x-b"));
+    assert!(!selector.matches("a.x"));
+
+    let selector = textmate_scope_selector_peg::parse_with_options("a.* b", &glob_options()).unwrap();
+    match selector.node() {
+        MatcherNode::Path(_, [scope, _]) => match scope.node() {
+            MatcherNode::Scope([_, wildcard]) => assert!(matches!(wildcard.node(), MatcherNode::Wildcard)),
+            _ => panic!("Expected a scope"),
+        },
+        _ => panic!("Expected a path"),
+    }
+}
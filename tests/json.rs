@@ -0,0 +1,18 @@
+#[test]
+fn test_json_string() {
+    let test_cases = vec![
+        ("source.js", "\"source.js\""),
+        ("a \"b\" \\ c", "\"a \\\"b\\\" \\\\ c\""),
+        ("a\n\tb\r", "\"a\\n\\tb\\r\""),
+        ("\u{1}é", "\"\\u0001é\""),
+    ];
+
+    for (value, expected) in test_cases {
+        assert_eq!(
+            textmate_scope_selector_peg::json::string(value),
+            expected,
+            "Test failed for value: {:?}",
+            value
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use textmate_scope_selector_peg::lint::{lint, LintKind, Severity};
+
+#[derive(Clone, Debug)]
+struct LintTestCase {
+    selectors: Vec<&'static str>,
+    expected: Vec<(usize, LintKind)>,
+}
+
+#[test]
+fn test_lint_diagnostics() {
+    let test_cases = vec![
+        LintTestCase {
+            selectors: vec!["comment", "string", "keyword"],
+            expected: vec![],
+        },
+        LintTestCase {
+            selectors: vec!["a,", "(b, ) - c", "d , e"],
+            expected: vec![(0, LintKind::TrailingComma { offset: 1 }), (1, LintKind::TrailingComma { offset: 2 })],
+        },
+        LintTestCase {
+            selectors: vec!["string.quoted", "string.quoted", "string.quoted  ,"],
+            expected: vec![
+                (1, LintKind::Duplicate { first: 0 }),
+                (2, LintKind::TrailingComma { offset: 15 }),
+                (2, LintKind::Duplicate { first: 0 }),
+            ],
+        },
+        LintTestCase {
+            selectors: vec!["a - a", "a & -a", "a.b - a", "a - a.b", "x - *", "-*, y - y"],
+            expected: vec![
+                (0, LintKind::NeverMatches),
+                (1, LintKind::NeverMatches),
+                (2, LintKind::NeverMatches),
+                (4, LintKind::NeverMatches),
+                (5, LintKind::NeverMatches),
+            ],
+        },
+        LintTestCase {
+            selectors: vec!["source.js string.quoted", "source.* string.quoted", "source.js string.quoted.double", "source.js string"],
+            expected: vec![],
+        },
+        LintTestCase {
+            selectors: vec!["string.quoted", "string.quoted - string.quoted.double", "string", "(a, b)", "a"],
+            expected: vec![],
+        },
+        LintTestCase {
+            selectors: vec!["a.b.c", "a.b.c d", "(x, a.b.c)", "a.b.c e, x.y", "(a, b.c.d)", "a"],
+            expected: vec![],
+        },
+        LintTestCase {
+            selectors: vec!["meta.tag.inline", "meta.tag.inline string", "string", "source string.quoted"],
+            expected: vec![],
+        },
+        LintTestCase {
+            selectors: vec!["a.b & c.d.e", "c.d.e a.b", "c.d.e a.b x", "x & y.z", "y.z c.d.e a.b - x.x"],
+            expected: vec![(1, LintKind::Shadowed { by: 0 }), (4, LintKind::Shadowed { by: 0 })],
+        },
+        LintTestCase {
+            selectors: vec!["L:source.js", "text - R:(comment)", "source.ts"],
+            expected: vec![(0, LintKind::Prefix), (1, LintKind::Prefix)],
+        },
+    ];
+
+    for case in test_cases {
+        let result: Vec<(usize, LintKind)> = lint(&case.selectors)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.index, diagnostic.kind))
+            .collect();
+        assert_eq!(
+            result, case.expected,
+            "Test failed for selectors: {:?}",
+            case.selectors
+        );
+    }
+}
+
+#[test]
+fn test_lint_syntax_errors() {
+    let diagnostics = lint(&["a, (b", "a", "a, (b"]);
+
+    assert_eq!(diagnostics.len(), 2);
+    for (diagnostic, index) in diagnostics.iter().zip([0, 2]) {
+        assert_eq!(diagnostic.index, index);
+        assert_eq!(diagnostic.severity(), Severity::Error);
+        match &diagnostic.kind {
+            LintKind::Syntax(err) => assert_eq!(err.location.offset, 5),
+            kind => panic!("Unexpected diagnostic: {:?}", kind),
+        }
+    }
+    assert!(diagnostics[0].to_string().starts_with("rule 0: invalid selector: error at 1:6"));
+}
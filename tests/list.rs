@@ -0,0 +1,113 @@
+use textmate_scope_selector_peg::matchers::Matcher;
+
+#[derive(Clone, Debug)]
+struct ManyTestCase {
+    selectors: Vec<&'static str>,
+    input: String,
+    expected: bool,
+    expected_source: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+struct LocationTestCase {
+    selector: String,
+    expected: (usize, usize, usize),
+}
+
+#[test]
+fn test_parse_many() {
+    let test_cases = vec![
+        ManyTestCase {
+            selectors: vec!["comment", "string.quoted, string.regexp", "keyword"],
+            input: "source.js string.regexp".to_string(),
+            expected: true,
+            expected_source: Some(1),
+        },
+        ManyTestCase {
+            selectors: vec!["comment", "string.quoted, string.regexp", "keyword"],
+            input: "source.js keyword.control".to_string(),
+            expected: true,
+            expected_source: Some(2),
+        },
+        ManyTestCase {
+            selectors: vec!["comment", "string - string.regexp"],
+            input: "string.regexp".to_string(),
+            expected: false,
+            expected_source: None,
+        },
+        ManyTestCase {
+            selectors: vec![],
+            input: "a".to_string(),
+            expected: false,
+            expected_source: None,
+        },
+    ];
+
+    for case in test_cases {
+        match textmate_scope_selector_peg::parse_many(&case.selectors) {
+            Ok(list) => {
+                assert_eq!(
+                    (list.matches(&case.input), list.source_of(&case.input)),
+                    (case.expected, case.expected_source),
+                    "Test failed for selectors: {:?}, input: \"{}\"",
+                    case.selectors, case.input
+                );
+            }
+            Err(err) => panic!("Parsing error for selectors {:?}: {}", &case.selectors, err),
+        }
+    }
+}
+
+#[test]
+fn test_parse_many_sources() {
+    let list = textmate_scope_selector_peg::parse_many(&["a, b", "(c, d) | e", "f - g"]).unwrap();
+    assert_eq!(list.sources(), &[0, 0, 1, 1, 1, 2]);
+}
+
+#[test]
+fn test_parse_many_error() {
+    let err = textmate_scope_selector_peg::parse_many(&["a", "b,\n  (c"]).err().unwrap();
+    assert_eq!(err.index, 1);
+    assert_eq!((err.error.location.line, err.error.location.column), (2, 5));
+    assert!(err.to_string().starts_with("selector 1: error at 2:5"));
+}
+
+#[test]
+fn test_multiline_selector() {
+    let selector = textmate_scope_selector_peg::parse("source.js\n  string.quoted,\r\n  comment\r\t- comment.block\n").unwrap();
+    assert!(selector.matches("source.js string.quoted.double"));
+    assert!(selector.matches("comment.line"));
+    assert!(!selector.matches("comment.block"));
+}
+
+#[test]
+fn test_multiline_error_location() {
+    let test_cases = vec![
+        LocationTestCase {
+            selector: "a,\nb,\n(c".to_string(),
+            expected: (3, 3, 8),
+        },
+        LocationTestCase {
+            selector: "a,\r\nb,\r\n(c".to_string(),
+            expected: (3, 3, 10),
+        },
+        LocationTestCase {
+            selector: "a,\rb,\r(c".to_string(),
+            expected: (3, 3, 8),
+        },
+        LocationTestCase {
+            selector: "a,\n  b &".to_string(),
+            expected: (2, 6, 8),
+        },
+    ];
+
+    for case in test_cases {
+        match textmate_scope_selector_peg::parse(&case.selector) {
+            Ok(_) => panic!("Expected a parsing error for selector {:?}", case.selector),
+            Err(err) => {
+                let location = (err.location.line, err.location.column, err.location.offset);
+                assert_eq!(location, case.expected, "Test failed for selector: {:?}", case.selector);
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+use std::process::Command;
+
+// Targets without `std` that the crate is built for. A missing target fails
+// the test unless `TMSEL_SKIP_NO_STD_TARGETS` is set
+const NO_STD_TARGETS: &[&str] = &["thumbv7em-none-eabihf", "wasm32v1-none"];
+
+fn cargo_build(target: Option<&str>) {
+    let mut command = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    command
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["build", "--lib", "--no-default-features", "--package", env!("CARGO_PKG_NAME")])
+        .arg("--target-dir")
+        .arg(Path::new(env!("CARGO_TARGET_TMPDIR")).join("no_std"));
+    if let Some(target) = target {
+        command.args(["--target", target]);
+    }
+
+    let output = command.output().expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "no_std build failed for target {:?}:\n{}",
+        target,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn installed_targets() -> Vec<String> {
+    Command::new("rustup")
+        .args(["target", "list", "--installed"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_build_without_std() {
+    // The crate is `#![no_std]`, so this fails on any use of `std` outside the feature
+    cargo_build(None);
+
+    let installed = installed_targets();
+    let skip_missing = std::env::var_os("TMSEL_SKIP_NO_STD_TARGETS").is_some();
+    for target in NO_STD_TARGETS {
+        if installed.iter().any(|installed| installed == target) {
+            cargo_build(Some(target));
+        } else if skip_missing {
+            eprintln!("skipping no_std target {} (not installed)", target);
+        } else {
+            panic!(
+                "no_std target {} is not installed: run `rustup target add {}`, or set TMSEL_SKIP_NO_STD_TARGETS=1 to skip it",
+                target, target
+            );
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use textmate_scope_selector_peg::ParseOptions;
+
+#[derive(Clone, Debug)]
+struct OptionsTestCase {
+    selector: String,
+    options: ParseOptions,
+    input: String,
+    expected: bool,
+}
+
+#[test]
+fn test_unicode_segments() {
+    let unicode = ParseOptions {
+        unicode_segments: true,
+        ..ParseOptions::default()
+    };
+    let test_cases = vec![
+        OptionsTestCase {
+            selector: "source.café".to_string(),
+            options: unicode.clone(),
+            input: "source.café string".to_string(),
+            expected: true,
+        },
+        OptionsTestCase {
+            selector: "meta.名前.ts".to_string(),
+            options: unicode.clone(),
+            input: "meta.名前.ts".to_string(),
+            expected: true,
+        },
+        OptionsTestCase {
+            selector: "meta.ñ - meta.ñ.x".to_string(),
+            options: unicode.clone(),
+            input: "meta.ñ.x".to_string(),
+            expected: false,
+        },
+    ];
+
+    for case in test_cases {
+        assert!(
+            textmate_scope_selector_peg::parse(&case.selector).is_err(),
+            "Expected the default grammar to reject selector: \"{}\"",
+            case.selector
+        );
+        match textmate_scope_selector_peg::parse_with_options(&case.selector, &case.options) {
+            Ok(selector) => {
+                let result = selector.matches(&case.input);
+                assert_eq!(
+                    result, case.expected,
+                    "Test failed for selector: \"{}\", input: \"{}\"",
+                    case.selector, case.input
+                );
+            }
+            Err(err) => panic!("Parsing error for selector {}: {}", &case.selector, err),
+        }
+    }
+}
+
+#[test]
+fn test_unicode_segments_reject_symbols() {
+    let options = ParseOptions {
+        unicode_segments: true,
+        ..ParseOptions::default()
+    };
+    for selector in ["a.→", "a.b·c d…", "a.\u{a0}b"] {
+        assert!(
+            textmate_scope_selector_peg::parse_with_options(selector, &options).is_err(),
+            "Expected a parsing error for selector: \"{}\"",
+            selector
+        );
+    }
+}
+
+#[test]
+fn test_case_insensitive() {
+    let test_cases = vec![
+        OptionsTestCase {
+            selector: "Source.JS String".to_string(),
+            options: ParseOptions::default(),
+            input: "source.js string.quoted".to_string(),
+            expected: false,
+        },
+        OptionsTestCase {
+            selector: "Source.JS String".to_string(),
+            options: ParseOptions {
+                case_insensitive: true,
+                ..ParseOptions::default()
+            },
+            input: "source.js string.quoted".to_string(),
+            expected: true,
+        },
+        OptionsTestCase {
+            selector: "source - COMMENT".to_string(),
+            options: ParseOptions {
+                case_insensitive: true,
+                ..ParseOptions::default()
+            },
+            input: "Source.Ts Comment.Line".to_string(),
+            expected: false,
+        },
+        OptionsTestCase {
+            selector: "meta.straße".to_string(),
+            options: ParseOptions {
+                unicode_segments: true,
+                case_insensitive: true,
+                ..ParseOptions::default()
+            },
+            input: "META.STRAßE".to_string(),
+            expected: true,
+        },
+    ];
+
+    for case in test_cases {
+        match textmate_scope_selector_peg::parse_with_options(&case.selector, &case.options) {
+            Ok(selector) => {
+                let result = selector.matches(&case.input);
+                assert_eq!(
+                    result, case.expected,
+                    "Test failed for selector: \"{}\", input: \"{}\"",
+                    case.selector, case.input
+                );
+            }
+            Err(err) => panic!("Parsing error for selector {}: {}", &case.selector, err),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use textmate_scope_selector_peg::matchers::Matcher;
+use textmate_scope_selector_peg::{ParseOptions, SelectorError};
+
+// Editor state the predicates read
+#[derive(Default)]
+struct EditorState {
+    readonly: AtomicBool,
+    in_injection: AtomicBool,
+}
+
+struct StateMatcher {
+    state: Arc<EditorState>,
+    read: fn(&EditorState) -> bool,
+}
+
+impl Matcher for StateMatcher {
+    fn matches(&self, _: &str) -> bool {
+        (self.read)(&self.state)
+    }
+}
+
+// Matches segments starting with an uppercase letter
+struct CapitalizedMatcher;
+
+impl Matcher for CapitalizedMatcher {
+    fn matches(&self, segment: &str) -> bool {
+        segment.starts_with(|c: char| c.is_ascii_uppercase())
+    }
+}
+
+fn predicate_options(state: &Arc<EditorState>) -> ParseOptions {
+    let mut options = ParseOptions::default();
+    let readonly = Arc::clone(state);
+    options.predicates.register("@readonly", move || {
+        Box::new(StateMatcher {
+            state: Arc::clone(&readonly),
+            read: |state| state.readonly.load(Ordering::Relaxed),
+        })
+    });
+    let in_injection = Arc::clone(state);
+    options.predicates.register("$inInjection", move || {
+        Box::new(StateMatcher {
+            state: Arc::clone(&in_injection),
+            read: |state| state.in_injection.load(Ordering::Relaxed),
+        })
+    });
+    options.predicates.register("@capitalized", || Box::new(CapitalizedMatcher));
+    options
+}
+
+#[derive(Clone, Debug)]
+struct PredicateTestCase {
+    selector: String,
+    readonly: bool,
+    in_injection: bool,
+    input: String,
+    expected: bool,
+}
+
+#[test]
+fn test_predicates() {
+    let test_cases = vec![
+        PredicateTestCase {
+            selector: "@readonly variable".to_string(),
+            readonly: true,
+            in_injection: false,
+            input: "source.js variable.other".to_string(),
+            expected: true,
+        },
+        PredicateTestCase {
+            selector: "@readonly variable".to_string(),
+            readonly: false,
+            in_injection: false,
+            input: "source.js variable.other".to_string(),
+            expected: false,
+        },
+        PredicateTestCase {
+            selector: "source.js $inInjection - comment".to_string(),
+            readonly: false,
+            in_injection: true,
+            input: "source.js string.quoted".to_string(),
+            expected: true,
+        },
+        PredicateTestCase {
+            selector: "source.js $inInjection - comment".to_string(),
+            readonly: false,
+            in_injection: true,
+            input: "source.js comment.line".to_string(),
+            expected: false,
+        },
+        PredicateTestCase {
+            selector: "-@readonly".to_string(),
+            readonly: true,
+            in_injection: false,
+            input: "source.js".to_string(),
+            expected: false,
+        },
+        PredicateTestCase {
+            selector: "entity.name.@capitalized".to_string(),
+            readonly: false,
+            in_injection: false,
+            input: "entity.name.Widget".to_string(),
+            expected: true,
+        },
+        PredicateTestCase {
+            selector: "entity.name.@capitalized".to_string(),
+            readonly: false,
+            in_injection: false,
+            input: "entity.name.widget".to_string(),
+            expected: false,
+        },
+    ];
+
+    for case in test_cases {
+        let state = Arc::new(EditorState::default());
+        let options = predicate_options(&state);
+        match textmate_scope_selector_peg::parse_with_options(&case.selector, &options) {
+            Ok(selector) => {
+                state.readonly.store(case.readonly, Ordering::Relaxed);
+                state.in_injection.store(case.in_injection, Ordering::Relaxed);
+                let result = selector.matches(&case.input);
+                assert_eq!(
+                    result, case.expected,
+                    "Test failed for selector: \"{}\", input: \"{}\"",
+                    case.selector, case.input
+                );
+            }
+            Err(err) => panic!("Parsing error for selector {}: {}", &case.selector, err),
+        }
+    }
+}
+
+#[test]
+fn test_predicates_display() {
+    let options = predicate_options(&Arc::new(EditorState::default()));
+    let selector = textmate_scope_selector_peg::parse_with_options("variable  @readonly,$inInjection", &options).unwrap();
+    assert_eq!(selector.to_string(), "variable @readonly, $inInjection");
+}
+
+#[test]
+fn test_unknown_predicates() {
+    let options = predicate_options(&Arc::new(EditorState::default()));
+    let test_cases = vec![
+        ("@deprecated", "@deprecated", 0),
+        ("source.js $inTemplate", "$inTemplate", 10),
+        ("variable @readonly - meta.@mutable", "@mutable", 26),
+    ];
+
+    for (selector, name, offset) in test_cases {
+        match textmate_scope_selector_peg::parse_with_options(selector, &options) {
+            Ok(_) => panic!("Expected a parsing error for selector: \"{}\"", selector),
+            Err(err) => {
+                assert_eq!(err.location().offset, offset, "Test failed for selector: \"{}\"", selector);
+                match err {
+                    SelectorError::UnknownPredicate { name: unknown, .. } => {
+                        assert_eq!(unknown, name, "Test failed for selector: \"{}\"", selector)
+                    }
+                    err => panic!("Expected an unknown predicate for selector \"{}\": {}", selector, err),
+                }
+            }
+        }
+    }
+
+    let err = textmate_scope_selector_peg::parse_with_options("a\n@b", &options).err().unwrap();
+    assert_eq!(err.to_string(), "error at 2:1: unknown predicate `@b`");
+}
+
+#[test]
+fn test_predicates_disabled() {
+    for selector in ["@readonly", "variable $inInjection"] {
+        assert!(
+            textmate_scope_selector_peg::parse(selector).is_err(),
+            "Expected a parsing error for selector: \"{}\"",
+            selector
+        );
+    }
+
+    // A sigil alone, or followed by a digit, is not a predicate
+    let options = predicate_options(&Arc::new(EditorState::default()));
+    for selector in ["@", "a $1"] {
+        match textmate_scope_selector_peg::parse_with_options(selector, &options) {
+            Err(SelectorError::Syntax(_)) => {}
+            result => panic!("Expected a syntax error for selector \"{}\": {:?}", selector, result.err()),
+        }
+    }
+}
+
+#[test]
+fn test_predicates_register() {
+    let mut options = ParseOptions::default();
+    options.predicates.register("@on", || Box::new(CapitalizedMatcher));
+    let copy = options.clone();
+    assert_eq!(options, copy);
+    assert_ne!(options, ParseOptions::default());
+
+    options.predicates.register("@on", || Box::new(CapitalizedMatcher));
+    assert_eq!(options.predicates.names().collect::<Vec<_>>(), vec!["@on"]);
+    assert_ne!(options, copy);
+    assert!(options.predicates.contains("@on"));
+    assert!(!options.predicates.contains("on"));
+    assert_eq!(format!("{:?}", options.predicates), "{\"@on\"}");
+}
+
+#[test]
+fn test_predicates_send_sync() {
+    let options = predicate_options(&Arc::new(EditorState::default()));
+    let parsed = std::thread::spawn(move || {
+        textmate_scope_selector_peg::parse_with_options("entity.@capitalized", &options)
+            .map(|selector| selector.matches("entity.Widget"))
+            .unwrap_or(false)
+    });
+    assert!(parsed.join().unwrap());
+}
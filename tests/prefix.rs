@@ -1,3 +1,6 @@
+// Written before clippy ran on test targets
+#![allow(clippy::single_component_path_imports, clippy::single_match)]
+
 use std::time::Instant;
 
 use textmate_scope_selector_peg;
@@ -0,0 +1,70 @@
+#![cfg(feature = "python")]
+
+use std::ffi::CString;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use textmate_scope_selector_peg::python::textmate_scope_selector_peg;
+
+#[derive(Clone, Debug)]
+struct PythonTestCase {
+    name: String,
+    code: String,
+}
+
+#[test]
+fn test_python_bindings() {
+    let test_cases = vec![
+        PythonTestCase {
+            name: "matches".to_string(),
+            code: r#"
+selector = tmsel.parse("source.js -comment")
+assert selector.matches("source.js string.quoted")
+assert selector.matches(["source.js", "string.quoted"])
+assert not selector.matches(["source.js", "comment.line"])
+"#.to_string(),
+        },
+        PythonTestCase {
+            name: "get_prefix".to_string(),
+            code: r#"
+selector = tmsel.parse("L:text.html.markdown - (comment, string)")
+assert selector.get_prefix(["text.html.markdown", "meta.paragraph.markdown"]) == "L"
+assert selector.get_prefix("text.html.markdown comment") is None
+"#.to_string(),
+        },
+        PythonTestCase {
+            name: "str".to_string(),
+            code: r#"
+selector = tmsel.parse("a&-b,c")
+assert str(selector) == "a - b, c"
+assert repr(selector) == 'Selector("a - b, c")'
+"#.to_string(),
+        },
+        PythonTestCase {
+            name: "errors".to_string(),
+            code: r#"
+try:
+    tmsel.parse("a, (b")
+except tmsel.SelectorError as error:
+    assert isinstance(error, ValueError)
+    assert (error.line, error.column, error.offset) == (1, 6, 5)
+    assert '")"' in error.expected
+else:
+    raise AssertionError("expected a SelectorError")
+"#.to_string(),
+        },
+    ];
+
+    Python::initialize();
+    Python::attach(|py| {
+        let module = pyo3::wrap_pymodule!(textmate_scope_selector_peg)(py);
+        for case in test_cases {
+            let locals = PyDict::new(py);
+            locals.set_item("tmsel", &module).unwrap();
+            let code = CString::new(case.code.as_str()).unwrap();
+            if let Err(err) = py.run(&code, None, Some(&locals)) {
+                panic!("Test failed for case {}: {}", case.name, err);
+            }
+        }
+    });
+}
@@ -0,0 +1,174 @@
+use textmate_scope_selector_peg::{ParseMode, ParseOptions, SelectorError, StrictIssue};
+
+#[derive(Clone, Debug)]
+struct RecoverTestCase {
+    selector: String,
+    expected_parsed: usize,
+    expected_errors: Vec<(usize, usize)>,
+    matching: Vec<&'static str>,
+    not_matching: Vec<&'static str>,
+}
+
+#[test]
+fn test_parse_recovering() {
+    let test_cases = vec![
+        RecoverTestCase {
+            selector: "comment, string - string.interpolated".to_string(),
+            expected_parsed: 2,
+            expected_errors: vec![],
+            matching: vec!["comment", "string"],
+            not_matching: vec!["string.interpolated", "keyword"],
+        },
+        RecoverTestCase {
+            selector: "comment, string &, keyword".to_string(),
+            expected_parsed: 2,
+            expected_errors: vec![(1, 18)],
+            matching: vec!["comment", "keyword"],
+            not_matching: vec!["string"],
+        },
+        RecoverTestCase {
+            selector: "(a, b) | c, d (, e".to_string(),
+            expected_parsed: 2,
+            expected_errors: vec![(1, 15)],
+            matching: vec!["a", "c", "e"],
+            not_matching: vec!["d"],
+        },
+        RecoverTestCase {
+            selector: "(a, (b), c".to_string(),
+            expected_parsed: 2,
+            expected_errors: vec![(1, 3)],
+            matching: vec!["b", "c"],
+            not_matching: vec!["a"],
+        },
+        RecoverTestCase {
+            selector: "a,,b,".to_string(),
+            expected_parsed: 2,
+            expected_errors: vec![(1, 3)],
+            matching: vec!["a", "b"],
+            not_matching: vec!["c"],
+        },
+        RecoverTestCase {
+            selector: "a,\n  b.,\n  c".to_string(),
+            expected_parsed: 2,
+            expected_errors: vec![(2, 5)],
+            matching: vec!["a", "c"],
+            not_matching: vec!["b"],
+        },
+        RecoverTestCase {
+            selector: "&".to_string(),
+            expected_parsed: 0,
+            expected_errors: vec![(1, 1)],
+            matching: vec![],
+            not_matching: vec!["a"],
+        },
+    ];
+
+    for case in test_cases {
+        let recovered = textmate_scope_selector_peg::parse_recovering(&case.selector);
+        let errors: Vec<(usize, usize)> = recovered
+            .errors
+            .iter()
+            .map(|err| (err.location.line, err.location.column))
+            .collect();
+        assert_eq!(
+            (recovered.parsed, errors), (case.expected_parsed, case.expected_errors.clone()),
+            "Test failed for selector: {:?}",
+            case.selector
+        );
+        assert_eq!(recovered.is_complete(), case.expected_errors.is_empty());
+
+        let matches = |input: &str| recovered.selector.as_ref().is_some_and(|selector| selector.matches(input));
+        for input in &case.matching {
+            assert!(matches(input), "Test failed for selector: {:?}, input: \"{}\"", case.selector, input);
+        }
+        for input in &case.not_matching {
+            assert!(!matches(input), "Test failed for selector: {:?}, input: \"{}\"", case.selector, input);
+        }
+    }
+}
+
+#[test]
+fn test_parse_recovering_with_options() {
+    let options = ParseOptions {
+        glob_segments: true,
+        ..ParseOptions::default()
+    };
+    let recovered = textmate_scope_selector_peg::parse_recovering_with_options("meta.*tag*, a.*b |, c", &options);
+    assert_eq!(recovered.parsed, 2);
+    assert_eq!(recovered.errors.len(), 1);
+    assert_eq!(recovered.errors[0].location().offset, 18);
+    let selector = recovered.selector.unwrap();
+    assert!(selector.matches("meta.html-tag"));
+    assert!(selector.matches("c"));
+    assert!(!selector.matches("a.xb"));
+
+    let mut options = ParseOptions::default();
+    options.predicates.register("@on", || Box::new(textmate_scope_selector_peg::matchers::TrueMatcher));
+    let recovered = textmate_scope_selector_peg::parse_recovering_with_options("a @on, @off, b", &options);
+    assert_eq!(recovered.parsed, 2);
+    match &recovered.errors[..] {
+        [SelectorError::UnknownPredicate { name, location }] => {
+            assert_eq!((name.as_str(), location.offset), ("@off", 7));
+        }
+        errors => panic!("Expected an unknown predicate: {:?}", errors),
+    }
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_parse_recovering_regex_segments() {
+    let options = ParseOptions {
+        regex_segments: true,
+        ..ParseOptions::default()
+    };
+    assert!(textmate_scope_selector_peg::parse_with_options("source./x{1,2}/", &options).is_ok());
+
+    let test_cases = vec![
+        ("source./x{1,2}/", 1, vec![]),
+        ("source./x{1,2}/, a.(, b./(,)|\\//", 2, vec![19]),
+        ("a./x,/.y, b", 2, vec![]),
+        ("a./x, b/ c, d", 2, vec![]),
+        ("a./x, b", 1, vec![4]),
+    ];
+
+    for (selector, parsed, offsets) in test_cases {
+        let recovered = textmate_scope_selector_peg::parse_recovering_with_options(selector, &options);
+        let errors: Vec<usize> = recovered.errors.iter().map(|err| err.location().offset).collect();
+        assert_eq!((recovered.parsed, errors), (parsed, offsets), "Test failed for selector: {:?}", selector);
+    }
+
+    // Without the option a `/` is no delimiter
+    let recovered = textmate_scope_selector_peg::parse_recovering("a./x, b/");
+    assert_eq!(recovered.parsed, 0);
+    assert_eq!(recovered.errors.len(), 2);
+}
+
+#[test]
+fn test_parse_recovering_strict() {
+    let options = ParseOptions {
+        mode: ParseMode::Strict,
+        ..ParseOptions::default()
+    };
+    let recovered = textmate_scope_selector_peg::parse_recovering_with_options("a,, b - L:c, d,", &options);
+    assert_eq!(recovered.parsed, 2);
+    let errors: Vec<_> = recovered
+        .errors
+        .iter()
+        .map(|err| match err {
+            SelectorError::Strict { issue, location } => (*issue, location.offset),
+            err => panic!("Expected a strict error: {}", err),
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            (StrictIssue::EmptyAlternative, 2),
+            (StrictIssue::StrayPrefix, 8),
+            (StrictIssue::TrailingComma, 14),
+        ]
+    );
+
+    // The lenient mode accepts a trailing comma, as `parse` does
+    let recovered = textmate_scope_selector_peg::parse_recovering_with_options("a, b,", &ParseOptions::default());
+    assert!(recovered.is_complete());
+}
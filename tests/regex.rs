@@ -0,0 +1,149 @@
+use textmate_scope_selector_peg::{ParseOptions, SelectorError};
+
+#[cfg(feature = "regex")]
+#[derive(Clone, Debug)]
+struct RegexTestCase {
+    selector: String,
+    input: String,
+    expected: bool,
+}
+
+#[cfg(feature = "regex")]
+fn regex_options() -> ParseOptions {
+    ParseOptions {
+        regex_segments: true,
+        ..ParseOptions::default()
+    }
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_segments() {
+    let test_cases = vec![
+        RegexTestCase {
+            selector: "source./(js|ts)x?/".to_string(),
+            input: "source.tsx meta.block".to_string(),
+            expected: true,
+        },
+        RegexTestCase {
+            selector: "source./(js|ts)x?/".to_string(),
+            input: "source.jsonc".to_string(),
+            expected: false,
+        },
+        RegexTestCase {
+            selector: "source./(js|ts)x?/ string - string./.*template.*/".to_string(),
+            input: "source.js string.template".to_string(),
+            expected: false,
+        },
+        RegexTestCase {
+            selector: "source./(js|ts)x?/ string - string./.*template.*/".to_string(),
+            input: "source.js string.quoted".to_string(),
+            expected: true,
+        },
+        RegexTestCase {
+            selector: "text./a\\/b/".to_string(),
+            input: "text.a/b".to_string(),
+            expected: true,
+        },
+        RegexTestCase {
+            selector: "/[a-z]+/.*".to_string(),
+            input: "Source.js".to_string(),
+            expected: false,
+        },
+    ];
+
+    for case in test_cases {
+        match textmate_scope_selector_peg::parse_with_options(&case.selector, &regex_options()) {
+            Ok(selector) => {
+                let result = selector.matches(&case.input);
+                assert_eq!(
+                    result, case.expected,
+                    "Test failed for selector: \"{}\", input: \"{}\"",
+                    case.selector, case.input
+                );
+            }
+            Err(err) => panic!("Parsing error for selector {}: {}", &case.selector, err),
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_segments_display() {
+    let options = ParseOptions {
+        case_insensitive: true,
+        ..regex_options()
+    };
+    let selector = textmate_scope_selector_peg::parse_with_options("source./(JS|TS)x?/  -comment", &options).unwrap();
+
+    assert_eq!(selector.to_string(), "source./(JS|TS)x?/ - comment");
+    assert!(selector.matches("source.tsx"));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_invalid_regex_segments() {
+    let test_cases = vec![
+        ("source./(js|ts/", 7, "unclosed group"),
+        ("source./a{2,1}/ b", 7, "invalid repetition count range"),
+        ("a - text./a\\/b/ x./\\\\(/", 18, "unclosed group"),
+    ];
+
+    for (selector, offset, message) in test_cases {
+        match textmate_scope_selector_peg::parse_with_options(selector, &regex_options()) {
+            Err(SelectorError::InvalidRegex { message: invalid, location }) => {
+                assert_eq!(location.offset, offset, "Test failed for selector: \"{}\"", selector);
+                assert!(invalid.contains(message), "Test failed for selector: \"{}\", message: {}", selector, invalid);
+            }
+            result => panic!("Expected an invalid regex for selector \"{}\": {:?}", selector, result.err()),
+        }
+    }
+
+    // An unterminated pattern is a syntax error
+    match textmate_scope_selector_peg::parse_with_options("source./js", &regex_options()) {
+        Err(SelectorError::Syntax(err)) => assert_eq!(err.location.offset, 10),
+        result => panic!("Expected a syntax error: {:?}", result.err()),
+    }
+
+    let err = textmate_scope_selector_peg::parse_with_options("source./(js|ts/", &regex_options()).err().unwrap();
+    assert!(err.to_string().starts_with("error at 1:8: regex parse error:"), "{}", err);
+}
+
+#[test]
+fn test_regex_segments_disabled() {
+    for selector in ["source./(js|ts)x?/", "/a/ b"] {
+        match textmate_scope_selector_peg::parse_with_options(selector, &ParseOptions::default()) {
+            Ok(_) => panic!("Expected a parsing error for selector: \"{}\"", selector),
+            Err(err) => assert!(
+                err.to_string().contains("regex"),
+                "Test failed for selector: \"{}\", error: {}",
+                selector,
+                err
+            ),
+        }
+    }
+}
+
+#[cfg(not(feature = "regex"))]
+#[test]
+fn test_regex_segments_without_feature() {
+    let options = ParseOptions {
+        regex_segments: true,
+        ..ParseOptions::default()
+    };
+    match textmate_scope_selector_peg::parse_with_options("source./(js|ts)x?/", &options) {
+        Err(SelectorError::Syntax(err)) => {
+            assert!(err.to_string().contains("the `regex` feature"), "{}", err)
+        }
+        result => panic!("Expected a syntax error: {:?}", result.err()),
+    }
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_spanned_regex_segments() {
+    // The tree keeps patterns as written, only matchers compile them
+    let tree = textmate_scope_selector_peg::parse_spanned_with_options("source./(js|ts/", &regex_options()).unwrap();
+    let segments: Vec<_> = tree.segments().into_iter().map(|segment| (segment.text, segment.span.clone())).collect();
+    assert_eq!(segments, vec![("source", 0..6), ("/(js|ts/", 7..15)]);
+}
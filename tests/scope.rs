@@ -0,0 +1,156 @@
+use std::cell::Cell;
+
+use textmate_scope_selector_peg::{ParseOptions, ScopeLike, ScopeStackLike, Segments};
+
+#[derive(Clone, Debug)]
+struct ScopeTestCase {
+    selector: String,
+    input: String,
+    expected: bool,
+}
+
+fn test_cases() -> Vec<ScopeTestCase> {
+    let cases = [
+        ("source.js", "source.js", true),
+        ("source.js", "source.js.jsx meta.block", true),
+        ("source.js", "source", false),
+        ("source.* string", "source.js meta.block string.quoted", true),
+        ("source.* string", "source string.quoted", false),
+        ("string.quoted - string.quoted.double", "string.quoted.double.js", false),
+        ("comment, (string & -string.quoted)", "source.js string.template", true),
+        ("L:source.js - comment", "source.js comment.line", false),
+        ("a b c", "a c b", false),
+        ("*", "", true),
+    ];
+
+    cases
+        .iter()
+        .map(|&(selector, input, expected)| ScopeTestCase {
+            selector: selector.to_string(),
+            input: input.to_string(),
+            expected,
+        })
+        .collect()
+}
+
+#[test]
+fn test_matches_stack() {
+    for case in test_cases() {
+        let selector = textmate_scope_selector_peg::parse(&case.selector).unwrap();
+        let scopes: Vec<&str> = case.input.split(' ').collect();
+        let owned: Vec<String> = scopes.iter().map(|scope| scope.to_string()).collect();
+
+        for result in [
+            selector.matches(&case.input),
+            selector.matches_stack(&case.input.as_str()),
+            selector.matches_stack(&scopes.as_slice()),
+            selector.matches_stack(&owned),
+        ] {
+            assert_eq!(
+                result, case.expected,
+                "Test failed for selector: \"{}\", input: \"{}\"",
+                case.selector, case.input
+            );
+        }
+    }
+}
+
+// Scopes interned as indices into a table of segments
+struct Interner {
+    segments: Vec<&'static str>,
+    visited: Cell<usize>,
+}
+
+struct InternedScope<'a> {
+    interner: &'a Interner,
+    segments: Vec<usize>,
+}
+
+impl ScopeLike for InternedScope<'_> {
+    fn for_each_segment(&self, visit: &mut dyn FnMut(&str) -> bool) {
+        for &segment in &self.segments {
+            self.interner.visited.set(self.interner.visited.get() + 1);
+            if !visit(self.interner.segments[segment]) {
+                break;
+            }
+        }
+    }
+}
+
+struct InternedStack<'a>(Vec<InternedScope<'a>>);
+
+impl ScopeStackLike for InternedStack<'_> {
+    fn for_each_scope(&self, visit: &mut dyn FnMut(&dyn ScopeLike) -> bool) {
+        for scope in &self.0 {
+            if !visit(scope) {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_custom_scope_types() {
+    let interner = Interner {
+        segments: vec!["source", "rust", "string", "quoted", "double", "meta", "block"],
+        visited: Cell::new(0),
+    };
+    let scope = |segments: &[usize]| InternedScope {
+        interner: &interner,
+        segments: segments.to_vec(),
+    };
+    let stack = InternedStack(vec![scope(&[0, 1]), scope(&[5, 6, 1]), scope(&[2, 3, 4, 1])]);
+
+    assert_eq!(stack.scopes_string(), "source.rust meta.block.rust string.quoted.double.rust");
+
+    let test_cases = [
+        ("source.rust string.quoted", true),
+        ("source.rust - meta.block", false),
+        ("source.* meta.* string.*", true),
+        ("string.quoted.single", false),
+    ];
+    for (selector, expected) in test_cases {
+        let matcher = textmate_scope_selector_peg::parse(selector).unwrap();
+        assert_eq!(
+            matcher.matches_stack(&stack),
+            expected,
+            "Test failed for selector: \"{}\"",
+            selector
+        );
+    }
+
+    // A path stops visiting once every scope matched, and a scope once every segment did
+    interner.visited.set(0);
+    let matcher = textmate_scope_selector_peg::parse("source").unwrap();
+    assert!(matcher.matches_stack(&stack));
+    assert_eq!(interner.visited.get(), 1);
+}
+
+#[test]
+fn test_matches_stack_with_options() {
+    let options = ParseOptions {
+        case_insensitive: true,
+        glob_segments: true,
+        ..ParseOptions::default()
+    };
+    let selector = textmate_scope_selector_peg::parse_with_options("Source.* meta.*tag*", &options).unwrap();
+    let scopes: &[&str] = &["text.html", "source.js", "meta.selftag.js"];
+
+    assert!(selector.matches_stack(&scopes));
+    assert!(!selector.matches_stack(&&scopes[..2]));
+}
+
+#[test]
+fn test_segments() {
+    let segments = ["string", "quoted", "double"];
+    assert_eq!(Segments(&segments).scope_name(), "string.quoted.double");
+
+    // Split scopes go through `Segments`, while a slice of names is a stack
+    let stack = [Segments(&["source", "js"]), Segments(&segments)];
+    assert_eq!(stack.as_slice().scopes_string(), "source.js string.quoted.double");
+    assert_eq!(["source", "js"].as_slice().scopes_string(), "source js");
+
+    let selector = textmate_scope_selector_peg::parse("source.js string.quoted").unwrap();
+    assert!(selector.matches_stack(&stack.as_slice()));
+    assert!(!selector.matches_stack(&["source", "js", "string.quoted"].as_slice()));
+}
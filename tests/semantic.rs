@@ -0,0 +1,117 @@
+use textmate_scope_selector_peg::semantic::{SemanticScopeMap, SemanticSelector, SemanticToken};
+
+#[derive(Clone, Debug)]
+struct SemanticTestCase {
+    token_type: &'static str,
+    modifiers: Vec<&'static str>,
+    language: Option<&'static str>,
+    expected: Option<Vec<&'static str>>,
+}
+
+#[test]
+fn test_resolve() {
+    let mut map = SemanticScopeMap::vscode_defaults();
+    map.insert("variable.readonly:typescript", ["variable.other.constant.ts"])
+        .unwrap();
+    map.insert("*.deprecated", ["invalid.deprecated"]).unwrap();
+
+    let test_cases = vec![
+        SemanticTestCase {
+            token_type: "variable",
+            modifiers: vec![],
+            language: None,
+            expected: Some(vec!["variable.other.readwrite", "entity.name.variable"]),
+        },
+        SemanticTestCase {
+            token_type: "variable",
+            modifiers: vec!["declaration", "readonly"],
+            language: Some("javascript"),
+            expected: Some(vec!["variable.other.constant"]),
+        },
+        SemanticTestCase {
+            token_type: "variable",
+            modifiers: vec!["readonly"],
+            language: Some("typescript"),
+            expected: Some(vec!["variable.other.constant.ts"]),
+        },
+        SemanticTestCase {
+            token_type: "variable",
+            modifiers: vec!["readonly", "defaultLibrary"],
+            language: Some("typescript"),
+            expected: Some(vec!["support.constant"]),
+        },
+        SemanticTestCase {
+            token_type: "enumMember",
+            modifiers: vec!["deprecated"],
+            language: None,
+            expected: Some(vec!["variable.other.enummember"]),
+        },
+        SemanticTestCase {
+            token_type: "unknown",
+            modifiers: vec!["deprecated"],
+            language: None,
+            expected: Some(vec!["invalid.deprecated"]),
+        },
+        SemanticTestCase {
+            token_type: "unknown",
+            modifiers: vec![],
+            language: None,
+            expected: None,
+        },
+    ];
+
+    for case in test_cases {
+        let token = SemanticToken {
+            token_type: case.token_type,
+            modifiers: &case.modifiers,
+            language: case.language,
+        };
+        let result: Option<Vec<&str>> = map
+            .resolve(&token)
+            .map(|scopes| scopes.iter().map(String::as_str).collect());
+        assert_eq!(result, case.expected, "Test failed for token: {:?}", token);
+    }
+}
+
+#[test]
+fn test_matches() {
+    let map = SemanticScopeMap::from_entries([
+        ("function", &["entity.name.function"][..]),
+        ("function.defaultLibrary", &["support.function.builtin", "support.function"][..]),
+    ])
+    .unwrap();
+    let selector = textmate_scope_selector_peg::parse("source.python support.function - comment").unwrap();
+
+    let builtin = SemanticToken {
+        token_type: "function",
+        modifiers: &["defaultLibrary"],
+        language: Some("python"),
+    };
+    let function = SemanticToken {
+        modifiers: &[],
+        ..builtin
+    };
+
+    assert!(map.matches(selector.as_ref(), "source.python", &builtin));
+    assert!(!map.matches(selector.as_ref(), "source.python comment.line", &builtin));
+    assert!(!map.matches(selector.as_ref(), "source.python", &function));
+}
+
+#[test]
+fn test_semantic_selectors() {
+    for selector in ["variable", "*.readonly", "variable.readonly.static:rust", "type_param-x:c-sharp"] {
+        match SemanticSelector::parse(selector) {
+            Ok(parsed) => assert_eq!(parsed.to_string(), selector),
+            Err(err) => panic!("Parsing error for selector {}: {}", selector, err),
+        }
+    }
+
+    for selector in ["", "variable.", ".readonly", "variable:", "1variable", "variable readonly"] {
+        assert!(
+            SemanticSelector::parse(selector).is_err(),
+            "Expected a parsing error for selector: \"{}\"",
+            selector
+        );
+    }
+    assert!(SemanticScopeMap::from_entries([("variable.", &["variable"][..])]).is_err());
+}
@@ -0,0 +1,92 @@
+use textmate_scope_selector_peg::specificity::{rank, specificity, winner, Specificity};
+
+#[derive(Clone, Debug)]
+struct SpecificityTestCase {
+    selector: String,
+    input: String,
+    expected: Option<(usize, usize, usize, usize)>,
+}
+
+#[test]
+fn test_specificity_order() {
+    let ordered = ["*", "string", "source string", "string.quoted", "source.js string.quoted", "string.quoted.double"];
+
+    for pair in ordered.windows(2) {
+        let lower = specificity(textmate_scope_selector_peg::parse(pair[0]).unwrap().as_ref());
+        let higher = specificity(textmate_scope_selector_peg::parse(pair[1]).unwrap().as_ref());
+        assert!(lower < higher, "Test failed for selectors: \"{}\" < \"{}\"", pair[0], pair[1]);
+    }
+}
+
+#[test]
+fn test_specificity_rank() {
+    let test_cases = vec![
+        SpecificityTestCase {
+            selector: "string, source.js string.quoted".to_string(),
+            input: "source.js string.quoted".to_string(),
+            expected: Some((2, 2, 2, 4)),
+        },
+        SpecificityTestCase {
+            selector: "string, source.js string.quoted".to_string(),
+            input: "source.ts string.quoted".to_string(),
+            expected: Some((2, 1, 1, 1)),
+        },
+        SpecificityTestCase {
+            selector: "string.quoted - comment".to_string(),
+            input: "string.quoted".to_string(),
+            expected: Some((1, 2, 1, 2)),
+        },
+        SpecificityTestCase {
+            selector: "string".to_string(),
+            input: "source.js string.quoted comment.block string.other".to_string(),
+            expected: Some((4, 1, 1, 1)),
+        },
+        SpecificityTestCase {
+            selector: "source.js string, comment".to_string(),
+            input: "source.js string.quoted comment.block".to_string(),
+            expected: Some((3, 1, 1, 1)),
+        },
+        SpecificityTestCase {
+            selector: "string.quoted - comment".to_string(),
+            input: "comment string.quoted".to_string(),
+            expected: None,
+        },
+    ];
+
+    for case in test_cases {
+        let selector = textmate_scope_selector_peg::parse(&case.selector).unwrap();
+        let result = rank(selector.as_ref(), &case.input);
+        let expected = case.expected.map(|(depth, last_scope_segments, scopes, segments)| Specificity {
+            depth,
+            last_scope_segments,
+            scopes,
+            segments,
+        });
+        assert_eq!(
+            result, expected,
+            "Test failed for selector: \"{}\", input: \"{}\"",
+            case.selector, case.input
+        );
+    }
+}
+
+#[test]
+fn test_winner_nested_stacks() {
+    let test_cases = vec![
+        (vec!["comment", "string"], "source.js string.quoted comment.block", Some(0)),
+        (vec!["source.js string.quoted", "comment"], "source.js string.quoted comment.block", Some(1)),
+        (vec!["meta.tag.inline", "meta.tag.inline string"], "text.html meta.tag.inline string.quoted", Some(1)),
+        (vec!["string", "string.quoted"], "source.js string.quoted string.other", Some(0)),
+        (vec!["source", "source"], "source.js", Some(1)),
+        (vec!["keyword"], "source.js", None),
+    ];
+
+    for (selectors, scopes, expected) in test_cases {
+        let matchers: Vec<_> = selectors
+            .iter()
+            .map(|selector| textmate_scope_selector_peg::parse(selector).unwrap())
+            .collect();
+        let result = winner(matchers.iter().map(|matcher| matcher.as_ref()), scopes);
+        assert_eq!(result, expected, "Test failed for selectors: {:?}, scopes: \"{}\"", selectors, scopes);
+    }
+}
@@ -0,0 +1,165 @@
+use textmate_scope_selector_peg::{ParseMode, ParseOptions, SelectorError, StrictIssue};
+
+#[derive(Clone, Debug)]
+struct StrictTestCase {
+    selector: String,
+    issue: StrictIssue,
+    offset: usize,
+}
+
+fn strict_options() -> ParseOptions {
+    ParseOptions {
+        mode: ParseMode::Strict,
+        ..ParseOptions::default()
+    }
+}
+
+#[test]
+fn test_strict_accepts() {
+    let test_cases = vec![
+        ("a, b", "a", true),
+        ("L:text.html - comment, R:source.js", "source.js", true),
+        ("L:(a | b)", "b", true),
+        ("a - b", "a b", false),
+        ("a-b", "a-b", true),
+        ("source.css meta.property-name", "source.css meta.property-name.css", true),
+        ("-a", "b", true),
+        ("a & -b", "a", true),
+        ("-a - b", "c", true),
+        ("a & -b - c", "a", true),
+        ("(a)\n-\tb", "a", true),
+        ("a -b", "a b", false),
+        ("(a)-b", "a", true),
+        ("source.js -comment", "source.js comment.line", false),
+        ("a-b - c", "a-b", true),
+        ("a.*- b", "a.c", true),
+    ];
+
+    for (selector, input, expected) in test_cases {
+        match textmate_scope_selector_peg::parse_with_options(selector, &strict_options()) {
+            Ok(matcher) => assert_eq!(
+                matcher.matches(input),
+                expected,
+                "Test failed for selector: \"{}\", input: \"{}\"",
+                selector,
+                input
+            ),
+            Err(err) => panic!("Parsing error for selector {}: {}", selector, err),
+        }
+    }
+}
+
+#[test]
+fn test_strict_rejects() {
+    let test_cases = vec![
+        StrictTestCase {
+            selector: "a,".to_string(),
+            issue: StrictIssue::TrailingComma,
+            offset: 1,
+        },
+        StrictTestCase {
+            selector: "a, b , ".to_string(),
+            issue: StrictIssue::TrailingComma,
+            offset: 5,
+        },
+        StrictTestCase {
+            selector: ",a".to_string(),
+            issue: StrictIssue::EmptyAlternative,
+            offset: 0,
+        },
+        StrictTestCase {
+            selector: "a,,b".to_string(),
+            issue: StrictIssue::EmptyAlternative,
+            offset: 2,
+        },
+        StrictTestCase {
+            selector: "a, ,b".to_string(),
+            issue: StrictIssue::EmptyAlternative,
+            offset: 3,
+        },
+        StrictTestCase {
+            selector: "a - L:b".to_string(),
+            issue: StrictIssue::StrayPrefix,
+            offset: 4,
+        },
+        StrictTestCase {
+            selector: "(R:a)".to_string(),
+            issue: StrictIssue::StrayPrefix,
+            offset: 1,
+        },
+        StrictTestCase {
+            selector: "a | B:b".to_string(),
+            issue: StrictIssue::StrayPrefix,
+            offset: 4,
+        },
+        StrictTestCase {
+            selector: "a- b".to_string(),
+            issue: StrictIssue::AmbiguousHyphen,
+            offset: 1,
+        },
+        StrictTestCase {
+            selector: "a-.b".to_string(),
+            issue: StrictIssue::AmbiguousHyphen,
+            offset: 1,
+        },
+        StrictTestCase {
+            selector: "source.js -comment-".to_string(),
+            issue: StrictIssue::AmbiguousHyphen,
+            offset: 18,
+        },
+    ];
+
+    for case in test_cases {
+        // Empty alternatives are syntax errors in the lenient mode too
+        let lenient = textmate_scope_selector_peg::parse_with_options(&case.selector, &ParseOptions::default());
+        assert!(
+            lenient.is_ok() || case.issue == StrictIssue::EmptyAlternative,
+            "Expected the lenient mode to accept selector: \"{}\"",
+            case.selector
+        );
+        match textmate_scope_selector_peg::parse_with_options(&case.selector, &strict_options()) {
+            Err(SelectorError::Strict { issue, location }) => {
+                assert_eq!(issue, case.issue, "Test failed for selector: \"{}\"", case.selector);
+                assert_eq!(location.offset, case.offset, "Test failed for selector: \"{}\"", case.selector);
+            }
+            result => panic!("Expected a strict error for selector \"{}\": {:?}", case.selector, result.err()),
+        }
+    }
+}
+
+#[test]
+fn test_strict_glob_hyphen() {
+    let options = ParseOptions {
+        glob_segments: true,
+        ..strict_options()
+    };
+    match textmate_scope_selector_peg::parse_with_options("meta.*tag- b", &options) {
+        Err(SelectorError::Strict { issue, location }) => {
+            assert_eq!(issue, StrictIssue::AmbiguousHyphen);
+            assert_eq!(location.offset, 9);
+        }
+        result => panic!("Expected a strict error: {:?}", result.err()),
+    }
+    assert!(textmate_scope_selector_peg::parse_with_options("meta.*tag-name - b", &options).is_ok());
+}
+
+#[test]
+fn test_strict_errors() {
+    let err = textmate_scope_selector_peg::parse_with_options("a,\n  b,", &strict_options()).err().unwrap();
+    assert_eq!(err.to_string(), "error at 2:4: trailing `,` without an alternative after it");
+
+    let err = textmate_scope_selector_peg::parse_with_options("a- b", &strict_options()).err().unwrap();
+    assert_eq!(err.to_string(), "error at 1:2: ambiguous `-`, write a subtraction as `a - b`");
+
+    // Other syntax errors are reported as in the lenient mode
+    for selector in ["a |", "(a", "a.", ""] {
+        let lenient = textmate_scope_selector_peg::parse_with_options(selector, &ParseOptions::default());
+        let strict = textmate_scope_selector_peg::parse_with_options(selector, &strict_options());
+        match (lenient, strict) {
+            (Err(SelectorError::Syntax(lenient)), Err(SelectorError::Syntax(strict))) => {
+                assert_eq!(lenient, strict, "Test failed for selector: \"{}\"", selector)
+            }
+            _ => panic!("Expected syntax errors for selector: \"{}\"", selector),
+        }
+    }
+}
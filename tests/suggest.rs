@@ -0,0 +1,68 @@
+use textmate_scope_selector_peg::suggest::suggest;
+
+#[derive(Clone, Debug)]
+struct SuggestTestCase {
+    input: &'static str,
+    expected: Vec<&'static str>,
+}
+
+#[test]
+fn test_suggest() {
+    let test_cases = vec![
+        SuggestTestCase {
+            input: "source.js string.quoted.double.js",
+            expected: vec![
+                "source.js string.quoted.double.js",
+                "source string.quoted.double.js",
+                "string.quoted.double.js",
+                "source.js string.quoted.double",
+                "source string.quoted.double",
+                "string.quoted.double",
+                "source.js string.quoted",
+                "source string.quoted",
+                "string.quoted",
+                "source.js string",
+                "source string",
+                "string",
+            ],
+        },
+        SuggestTestCase {
+            input: "comment.line",
+            expected: vec!["comment.line", "comment"],
+        },
+        SuggestTestCase {
+            input: "source.c meta.block/x string.a/b",
+            expected: vec!["source.c string", "source string", "meta string", "string"],
+        },
+        SuggestTestCase {
+            input: "",
+            expected: vec![],
+        },
+    ];
+
+    for case in test_cases {
+        let result: Vec<String> = suggest(case.input)
+            .into_iter()
+            .map(|suggestion| suggestion.selector)
+            .collect();
+        assert_eq!(result, case.expected, "Test failed for input: \"{}\"", case.input);
+    }
+}
+
+#[test]
+fn test_suggestions_parse_and_match() {
+    let input = "text.html.basic meta.tag.inline.a.html source.js.embedded.html meta.function.js string.quoted.double.js";
+    let suggestions = suggest(input);
+
+    assert_eq!(suggestions[0].selector, input);
+    assert!(suggestions.windows(2).all(|pair| pair[0].specificity >= pair[1].specificity));
+    for suggestion in &suggestions {
+        let selector = textmate_scope_selector_peg::parse(&suggestion.selector).unwrap();
+        assert!(
+            selector.matches(input),
+            "Test failed for selector: \"{}\", input: \"{}\"",
+            suggestion.selector,
+            input
+        );
+    }
+}
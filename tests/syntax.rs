@@ -0,0 +1,205 @@
+use textmate_scope_selector_peg::syntax::{Node, Operator, Prefix, Scope, Segment};
+use textmate_scope_selector_peg::{ParseMode, ParseOptions, SelectorError, StrictIssue};
+
+fn segment(text: &str, start: usize) -> Segment<'_> {
+    Segment {
+        text,
+        span: start..start + text.len(),
+    }
+}
+
+#[test]
+fn test_spanned_path() {
+    let tree = textmate_scope_selector_peg::parse_spanned("L:source.js  string.*").unwrap();
+    assert_eq!(
+        tree,
+        Node::Path {
+            prefix: Some(Prefix { side: 'L', span: 0..2 }),
+            scopes: vec![
+                Scope {
+                    segments: vec![segment("source", 2), segment("js", 9)],
+                    span: 2..11,
+                },
+                Scope {
+                    segments: vec![segment("string", 13), segment("*", 20)],
+                    span: 13..21,
+                },
+            ],
+            span: 0..21,
+        }
+    );
+}
+
+#[test]
+fn test_spanned_operators() {
+    let tree = textmate_scope_selector_peg::parse_spanned(" R:(a | b) - c, -d ").unwrap();
+    let expected = Node::Binary {
+        left: Box::new(Node::Binary {
+            left: Box::new(Node::Group {
+                prefix: Some(Prefix { side: 'R', span: 1..3 }),
+                open: 3..4,
+                selector: Box::new(Node::Binary {
+                    left: Box::new(Node::Path {
+                        prefix: None,
+                        scopes: vec![Scope {
+                            segments: vec![segment("a", 4)],
+                            span: 4..5,
+                        }],
+                        span: 4..5,
+                    }),
+                    operator: Operator { symbol: '|', span: 6..7 },
+                    right: Box::new(Node::Path {
+                        prefix: None,
+                        scopes: vec![Scope {
+                            segments: vec![segment("b", 8)],
+                            span: 8..9,
+                        }],
+                        span: 8..9,
+                    }),
+                    span: 4..9,
+                }),
+                close: 9..10,
+                span: 1..10,
+            }),
+            operator: Operator { symbol: '-', span: 11..12 },
+            right: Box::new(Node::Path {
+                prefix: None,
+                scopes: vec![Scope {
+                    segments: vec![segment("c", 13)],
+                    span: 13..14,
+                }],
+                span: 13..14,
+            }),
+            span: 1..14,
+        }),
+        operator: Operator { symbol: ',', span: 14..15 },
+        right: Box::new(Node::Negate {
+            operator: 16..17,
+            operand: Box::new(Node::Path {
+                prefix: None,
+                scopes: vec![Scope {
+                    segments: vec![segment("d", 17)],
+                    span: 17..18,
+                }],
+                span: 17..18,
+            }),
+            span: 16..18,
+        }),
+        span: 1..18,
+    };
+    assert_eq!(tree, expected);
+}
+
+#[derive(Clone, Debug)]
+struct SegmentAtTestCase {
+    selector: String,
+    offset: usize,
+    expected: Option<(String, String)>,
+}
+
+#[test]
+fn test_segment_at() {
+    let test_cases = vec![
+        SegmentAtTestCase {
+            selector: "source.js - (comment.line, string)".to_string(),
+            offset: 8,
+            expected: Some(("source.js".to_string(), "js".to_string())),
+        },
+        SegmentAtTestCase {
+            selector: "source.js - (comment.line, string)".to_string(),
+            offset: 22,
+            expected: Some(("comment.line".to_string(), "line".to_string())),
+        },
+        SegmentAtTestCase {
+            selector: "source.js - (comment.line, string)".to_string(),
+            offset: 10,
+            expected: None,
+        },
+        SegmentAtTestCase {
+            selector: "source.js - (comment.line, string)".to_string(),
+            offset: 6,
+            expected: None,
+        },
+        SegmentAtTestCase {
+            selector: "source.js - (comment.line, string)".to_string(),
+            offset: 34,
+            expected: None,
+        },
+    ];
+
+    for case in test_cases {
+        let tree = textmate_scope_selector_peg::parse_spanned(&case.selector).unwrap();
+        let result = tree.segment_at(case.offset).map(|(scope, segment)| {
+            (case.selector[scope.span.clone()].to_string(), segment.text.to_string())
+        });
+        assert_eq!(
+            result, case.expected,
+            "Test failed for selector: \"{}\", offset: {}",
+            case.selector, case.offset
+        );
+    }
+}
+
+#[test]
+fn test_spanned_segments() {
+    let selector = "string.quoted - string.quoted.double, (meta.string)";
+    let tree = textmate_scope_selector_peg::parse_spanned(selector).unwrap();
+    let spans: Vec<_> = tree.segments().into_iter().filter(|segment| segment.text == "string").map(|segment| segment.span.clone()).collect();
+    assert_eq!(spans, vec![0..6, 16..22, 44..50]);
+    for span in spans {
+        assert_eq!(&selector[span], "string");
+    }
+}
+
+#[test]
+fn test_spanned_with_options() {
+    let options = ParseOptions {
+        glob_segments: true,
+        ..ParseOptions::default()
+    };
+    let tree = textmate_scope_selector_peg::parse_spanned_with_options("meta.*tag*", &options).unwrap();
+    assert_eq!(tree.segments(), vec![&segment("meta", 0), &segment("*tag*", 5)]);
+
+    let options = ParseOptions {
+        mode: ParseMode::Strict,
+        ..ParseOptions::default()
+    };
+    match textmate_scope_selector_peg::parse_spanned_with_options("a, b,", &options) {
+        Err(SelectorError::Strict { issue, location }) => {
+            assert_eq!(issue, StrictIssue::TrailingComma);
+            assert_eq!(location.offset, 4);
+        }
+        result => panic!("Expected a strict error: {:?}", result),
+    }
+}
+
+#[test]
+fn test_spanned_agrees_with_parse() {
+    let selectors = ["a", "a,", "a.b  c", "a - b - c", "(a) | -b & c", "L:a, R:(b)", "a,,b", "a -", "(a", ""];
+    for selector in selectors {
+        let spanned = textmate_scope_selector_peg::parse_spanned(selector);
+        let parsed = textmate_scope_selector_peg::parse(selector);
+        match (spanned, parsed) {
+            (Ok(tree), Ok(_)) => assert!(tree.span().end <= selector.len(), "Test failed for selector: \"{}\"", selector),
+            (Err(spanned), Err(parsed)) => assert_eq!(spanned, parsed, "Test failed for selector: \"{}\"", selector),
+            _ => panic!("Test failed for selector: \"{}\"", selector),
+        }
+    }
+}
+
+#[test]
+fn test_parser_entry_points() {
+    use textmate_scope_selector_peg::parser;
+
+    let selector = parser::parse("source.js - comment").unwrap();
+    assert!(selector.matches("source.js string.quoted"));
+    assert!(!selector.matches("source.js comment.line"));
+    assert!(parser::parse("source.js -").is_err());
+
+    let options = ParseOptions {
+        glob_segments: true,
+        ..ParseOptions::default()
+    };
+    let selector = parser::parse_with_options("meta.*tag*", &options).unwrap();
+    assert!(selector.matches("meta.html-tag"));
+}
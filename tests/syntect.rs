@@ -0,0 +1,166 @@
+#![cfg(feature = "syntect")]
+
+use std::str::FromStr;
+
+use syntect::highlighting::ScopeSelectors;
+use syntect::parsing::ScopeStack;
+use textmate_scope_selector_peg::syntect::{from_scope_selectors, matches, to_scope_selectors, ConversionError};
+
+#[derive(Clone, Debug)]
+struct SyntectTestCase {
+    selector: String,
+    syntect_selector: String,
+    input: String,
+    expected: bool,
+}
+
+fn test_cases() -> Vec<SyntectTestCase> {
+    // The same selector in both syntaxes, where they differ
+    let cases = [
+        ("source.php meta.preprocessor - string.quoted", None, "source.php meta.preprocessor.php string.quoted", false),
+        ("source.php meta.preprocessor - string.quoted", None, "source.php meta.preprocessor.php", true),
+        ("source string, comment", None, "source.js comment.line", true),
+        ("source string, comment", None, "source.js keyword", false),
+        ("a.b c e.f", None, "a.b c.d j e.f.g", true),
+        ("a c.d.e", None, "a.b c.d e.f.g", false),
+        (
+            "string - (string.quoted, string.unquoted)",
+            Some("string - string.quoted - string.unquoted"),
+            "string.unquoted.js",
+            false,
+        ),
+        (
+            "string - (string.quoted, string.unquoted)",
+            Some("string - string.quoted - string.unquoted"),
+            "string.interpolated.js",
+            true,
+        ),
+        ("text.html | source.css", None, "text.html.basic source.css.embedded", true),
+        ("keyword.control.flow.return.rust", None, "source.rust keyword.control.flow", false),
+    ];
+
+    cases
+        .iter()
+        .map(|&(selector, syntect_selector, input, expected)| SyntectTestCase {
+            selector: selector.to_string(),
+            syntect_selector: syntect_selector.unwrap_or(selector).to_string(),
+            input: input.to_string(),
+            expected,
+        })
+        .collect()
+}
+
+#[test]
+fn test_matches_scope_stack() {
+    for case in test_cases() {
+        let selector = textmate_scope_selector_peg::parse(&case.selector).unwrap();
+        let stack = ScopeStack::from_str(&case.input).unwrap();
+
+        let result = matches(selector.as_ref(), stack.as_slice());
+        assert_eq!(
+            result, case.expected,
+            "Test failed for selector: \"{}\", input: \"{}\"",
+            case.selector, case.input
+        );
+    }
+}
+
+#[test]
+fn test_from_scope_selectors() {
+    for case in test_cases() {
+        let selectors = ScopeSelectors::from_str(&case.syntect_selector).unwrap();
+        let stack = ScopeStack::from_str(&case.input).unwrap();
+        let matcher = from_scope_selectors(&selectors).unwrap();
+
+        assert_eq!(
+            selectors.does_match(stack.as_slice()).is_some(),
+            case.expected,
+            "Test failed for syntect selector: \"{}\", input: \"{}\"",
+            case.syntect_selector,
+            case.input
+        );
+        assert_eq!(
+            matcher.matches(&case.input),
+            case.expected,
+            "Test failed for selector: \"{}\", input: \"{}\"",
+            matcher,
+            case.input
+        );
+    }
+}
+
+#[test]
+fn test_to_scope_selectors() {
+    for case in test_cases() {
+        let selector = textmate_scope_selector_peg::parse(&case.selector).unwrap();
+        let stack = ScopeStack::from_str(&case.input).unwrap();
+        let selectors = to_scope_selectors(selector.as_ref()).unwrap();
+
+        assert_eq!(
+            selectors.does_match(stack.as_slice()).is_some(),
+            case.expected,
+            "Test failed for selector: \"{}\", input: \"{}\"",
+            case.selector,
+            case.input
+        );
+        assert_eq!(from_scope_selectors(&selectors).unwrap().to_string(), selector.to_string().replace(" | ", ", "));
+    }
+}
+
+#[test]
+fn test_conversion_edge_cases() {
+    // syntect excludes each ` -` operand, where `parse` nests them to the right
+    let selectors = ScopeSelectors::from_str("string - string.quoted - string.unquoted").unwrap();
+    let matcher = from_scope_selectors(&selectors).unwrap();
+    assert_eq!(matcher.to_string(), "string - (string.quoted, string.unquoted)");
+    assert!(!matcher.matches("string.unquoted.js"));
+
+    let selectors = ScopeSelectors::from_str(" - comment").unwrap();
+    let matcher = from_scope_selectors(&selectors).unwrap();
+    assert_eq!(matcher.to_string(), "* - comment");
+    assert!(matcher.matches(""));
+    assert!(!matcher.matches("source.js comment.line"));
+
+    let selector = textmate_scope_selector_peg::parse("* - (comment, string)").unwrap();
+    let selectors = to_scope_selectors(selector.as_ref()).unwrap();
+    assert_eq!(selectors.selectors.len(), 1);
+    assert!(selectors.selectors[0].path.is_empty());
+    assert_eq!(selectors.selectors[0].excludes.len(), 2);
+
+    for (selector, expected) in [
+        ("L:source.js", "Prefix"),
+        ("source.* string", "Segment"),
+        ("a.*.b", "Segment"),
+        ("a & b", "Operator"),
+        ("a - (b - c)", "Operator"),
+        ("a.b.c.d.e.f.g.h.i", "Scope"),
+    ] {
+        let parsed = textmate_scope_selector_peg::parse(selector).unwrap();
+        match to_scope_selectors(parsed.as_ref()) {
+            Ok(_) => panic!("Expected a conversion error for selector: \"{}\"", selector),
+            Err(err) => assert!(
+                format!("{:?}", err).starts_with(expected),
+                "Test failed for selector: \"{}\", error: {}",
+                selector,
+                err
+            ),
+        }
+    }
+    assert!(ConversionError::Prefix.to_string().contains("prefixes"));
+}
+
+#[test]
+fn test_scope_stack_like() {
+    for case in test_cases() {
+        let selector = textmate_scope_selector_peg::parse(&case.selector).unwrap();
+        let stack = ScopeStack::from_str(&case.input).unwrap();
+
+        assert_eq!(
+            selector.matches_stack(&stack),
+            case.expected,
+            "Test failed for selector: \"{}\", input: \"{}\"",
+            case.selector,
+            case.input
+        );
+    }
+}
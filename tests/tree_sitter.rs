@@ -0,0 +1,102 @@
+use textmate_scope_selector_peg::tree_sitter::CaptureScopeMap;
+
+#[derive(Clone, Debug)]
+struct CaptureTestCase {
+    captures: Vec<&'static str>,
+    expected: &'static str,
+}
+
+#[test]
+fn test_scope_stack() {
+    let mut map = CaptureScopeMap::defaults().with_language("rust");
+    map.insert("@punctuation.bracket", "punctuation.section.brackets");
+
+    let test_cases = vec![
+        CaptureTestCase {
+            captures: vec!["@keyword.control.return"],
+            expected: "source.rust keyword.control.return.rust",
+        },
+        CaptureTestCase {
+            captures: vec!["@string", "@string.special"],
+            expected: "source.rust string.rust string.other.rust",
+        },
+        CaptureTestCase {
+            captures: vec!["function.builtin.static"],
+            expected: "source.rust support.function.static.rust",
+        },
+        CaptureTestCase {
+            captures: vec!["@functional"],
+            expected: "source.rust functional.rust",
+        },
+        CaptureTestCase {
+            captures: vec!["@attribute", "@punctuation.bracket.round"],
+            expected: "source.rust entity.other.attribute-name.rust punctuation.section.brackets.round.rust",
+        },
+        CaptureTestCase {
+            captures: vec![],
+            expected: "source.rust",
+        },
+    ];
+
+    for case in test_cases {
+        let result = map.scope_stack("source.rust", case.captures.iter().copied());
+        assert_eq!(result, case.expected, "Test failed for captures: {:?}", case.captures);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CaptureMatchTestCase {
+    selector: &'static str,
+    captures: Vec<&'static str>,
+    expected: bool,
+}
+
+#[test]
+fn test_matches() {
+    let map = CaptureScopeMap::defaults();
+    let test_cases = vec![
+        CaptureMatchTestCase {
+            selector: "keyword.control",
+            captures: vec!["@keyword.control.return"],
+            expected: true,
+        },
+        CaptureMatchTestCase {
+            selector: "string - string.other",
+            captures: vec!["@string", "@string.special"],
+            expected: false,
+        },
+        CaptureMatchTestCase {
+            selector: "source.js string constant.character.escape",
+            captures: vec!["@string", "@string.escape"],
+            expected: true,
+        },
+        CaptureMatchTestCase {
+            selector: "entity.name.function, support.function",
+            captures: vec!["@function.builtin"],
+            expected: true,
+        },
+        CaptureMatchTestCase {
+            selector: "source.rust",
+            captures: vec!["@comment"],
+            expected: false,
+        },
+    ];
+
+    for case in test_cases {
+        let selector = textmate_scope_selector_peg::parse(case.selector).unwrap();
+        let result = map.matches(selector.as_ref(), "source.js", case.captures.iter().copied());
+        assert_eq!(
+            result, case.expected,
+            "Test failed for selector: \"{}\", captures: {:?}",
+            case.selector, case.captures
+        );
+    }
+}
+
+#[test]
+fn test_identity_map() {
+    let map = CaptureScopeMap::new();
+
+    assert_eq!(map.scope("@string.special"), "string.special");
+    assert_eq!(map.scope_stack("", ["@comment", "@comment.documentation"]), "comment comment.documentation");
+}
@@ -0,0 +1,118 @@
+use textmate_scope_selector_peg::lint::Severity;
+use textmate_scope_selector_peg::validate::{validate, validate_with_language, validate_with_options, ScopeIssue};
+use textmate_scope_selector_peg::ParseOptions;
+
+#[derive(Clone, Debug)]
+struct ValidateTestCase {
+    scopes: &'static str,
+    expected: Vec<(usize, ScopeIssue)>,
+}
+
+#[test]
+fn test_validate() {
+    let test_cases = vec![
+        ValidateTestCase {
+            scopes: "source.js string.quoted.double.js punctuation.definition.string.begin.js",
+            expected: vec![(34, ScopeIssue::NonStandardRoot)],
+        },
+        ValidateTestCase {
+            scopes: "text.html.basic meta.tag.html source.css.embedded.html meta.property-list.css",
+            expected: vec![],
+        },
+        ValidateTestCase {
+            scopes: "source.js keyword.control",
+            expected: vec![(25, ScopeIssue::MissingLanguageSuffix)],
+        },
+        ValidateTestCase {
+            scopes: "source comment",
+            expected: vec![(6, ScopeIssue::MissingLanguageSuffix), (14, ScopeIssue::MissingLanguageSuffix)],
+        },
+        ValidateTestCase {
+            scopes: "keyword.control meta.block",
+            expected: vec![(0, ScopeIssue::UnknownLanguage)],
+        },
+        ValidateTestCase {
+            scopes: "source.c++ entity.name.function.c++",
+            expected: vec![],
+        },
+        ValidateTestCase {
+            scopes: "source.js  string..js meta.-x.js",
+            expected: vec![(18, ScopeIssue::EmptySegment), (27, ScopeIssue::LeadingHyphen)],
+        },
+        ValidateTestCase {
+            scopes: "source.js string.quoted/double.js constant.numeric.é.js",
+            expected: vec![(23, ScopeIssue::InvalidCharacter('/')), (51, ScopeIssue::InvalidCharacter('é'))],
+        },
+        ValidateTestCase {
+            scopes: "sourcejs",
+            expected: vec![(0, ScopeIssue::NonStandardRoot), (8, ScopeIssue::MissingLanguageSuffix)],
+        },
+    ];
+
+    for case in test_cases {
+        let result: Vec<(usize, ScopeIssue)> = validate(case.scopes)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.offset, diagnostic.issue))
+            .collect();
+        assert_eq!(result, case.expected, "Test failed for scopes: \"{}\"", case.scopes);
+    }
+}
+
+#[test]
+fn test_validate_with_options() {
+    let options = ParseOptions {
+        unicode_segments: true,
+        ..ParseOptions::default()
+    };
+
+    assert!(validate_with_options("source.js constant.numeric.é.js", &options).is_empty());
+
+    let diagnostics = validate_with_options("source.js constant.numeric.→.js", &options);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].issue, ScopeIssue::InvalidCharacter('→'));
+    assert_eq!(diagnostics[0].severity(), Severity::Error);
+    assert_eq!(diagnostics[0].to_string(), "offset 27: character '→' is not allowed in a segment");
+}
+
+#[test]
+fn test_valid_scopes_parse_as_selectors() {
+    let scopes = "source.js meta.function.js string.quoted.double-escape.js";
+
+    assert!(validate(scopes).is_empty());
+    let selector = textmate_scope_selector_peg::parse(scopes).unwrap();
+    assert!(selector.matches(scopes));
+}
+
+#[test]
+fn test_validate_with_language() {
+    let test_cases = vec![
+        ValidateTestCase {
+            scopes: "keyword.control",
+            expected: vec![(15, ScopeIssue::MissingLanguageSuffix)],
+        },
+        ValidateTestCase {
+            scopes: "keyword.control.js meta.block.js",
+            expected: vec![],
+        },
+        ValidateTestCase {
+            scopes: "source.ts keyword.control.ts string.js",
+            expected: vec![],
+        },
+        ValidateTestCase {
+            scopes: "source.ts keyword.control.tsx",
+            expected: vec![(29, ScopeIssue::MissingLanguageSuffix)],
+        },
+    ];
+
+    for case in test_cases {
+        let result: Vec<(usize, ScopeIssue)> = validate_with_language(case.scopes, "js")
+            .into_iter()
+            .map(|diagnostic| (diagnostic.offset, diagnostic.issue))
+            .collect();
+        assert_eq!(result, case.expected, "Test failed for scopes: \"{}\"", case.scopes);
+    }
+
+    let diagnostics = validate("keyword.control");
+    assert_eq!(diagnostics[0].severity(), Severity::Warning);
+    assert_eq!(diagnostics[0].to_string(), "offset 0: no language to check suffixes against");
+}
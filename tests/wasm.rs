@@ -0,0 +1,38 @@
+#![cfg(all(feature = "wasm-bindgen", target_arch = "wasm32"))]
+
+use js_sys::Array;
+use textmate_scope_selector_peg::wasm::ScopeSelector;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn scopes(scopes: &[&str]) -> JsValue {
+    scopes.iter().map(|scope| JsValue::from_str(scope)).collect::<Array>().into()
+}
+
+#[wasm_bindgen_test]
+fn test_scope_selector_matches() {
+    let selector = ScopeSelector::new("source.js -comment").unwrap();
+    assert!(selector.matches(scopes(&["source.js", "string.quoted"])));
+    assert!(!selector.matches(scopes(&["source.js", "comment.line"])));
+    assert!(selector.matches(JsValue::from_str("source.js")));
+}
+
+#[wasm_bindgen_test]
+fn test_scope_selector_get_prefix() {
+    let selector = ScopeSelector::new("L:text.html.markdown - (comment, string)").unwrap();
+    assert_eq!(selector.get_prefix(scopes(&["text.html.markdown", "meta.paragraph.markdown"])), Some("L".to_string()));
+    assert_eq!(selector.get_prefix(scopes(&["text.html.markdown", "comment"])), None);
+}
+
+#[wasm_bindgen_test]
+fn test_scope_selector_to_string() {
+    let selector = ScopeSelector::new("a&-b,(c|d)").unwrap();
+    assert_eq!(selector.to_string(), "a - b, (c, d)");
+}
+
+#[wasm_bindgen_test]
+fn test_scope_selector_parse_error() {
+    assert!(ScopeSelector::new("a, (b").is_err());
+}
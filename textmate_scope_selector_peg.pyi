@@ -0,0 +1,15 @@
+from typing import List, Optional, Union
+
+Scopes = Union[str, List[str]]
+
+class Selector:
+    def matches(self, scopes: Scopes) -> bool: ...
+    def get_prefix(self, scopes: Scopes) -> Optional[str]: ...
+
+class SelectorError(ValueError):
+    line: int
+    column: int
+    offset: int
+    expected: List[str]
+
+def parse(selector: str) -> Selector: ...